@@ -0,0 +1,397 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! List of redundant [`BaseUrl`]s with endpoint selection and failover
+use std::sync::{
+	atomic::{AtomicBool, AtomicUsize, Ordering},
+	Arc,
+};
+
+#[cfg(all(feature = "serde", feature = "schemars"))]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::BaseUrl;
+
+/// How [`BaseUrls::select`] picks an endpoint among the healthy ones
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(all(feature = "serde", feature = "schemars"), derive(JsonSchema))]
+pub enum SelectionStrategy {
+	/// First healthy endpoint in configuration order, i.e. the rest are
+	/// fallbacks
+	#[default]
+	Priority,
+	/// Each selection starts at the next endpoint
+	RoundRobin,
+	/// Each selection starts at a random endpoint
+	Random,
+}
+
+/// Non-empty, deduplicated list of redundant [`BaseUrl`]s. Endpoints can be
+/// marked unhealthy, after which they are only selected if all endpoints are
+/// unhealthy. Clones share the health state and round-robin position.
+///
+/// Deserializes from a single URL, a list of URLs, or an object with `urls` and
+/// `strategy` (see [`SelectionStrategy`], `priority` by default).
+/// ```
+/// # use famedly_rust_utils::{BaseUrls, SelectionStrategy};
+/// let urls: BaseUrls = serde_json::from_value(serde_json::json!({
+/// 	"urls": ["https://a.example.com", "https://b.example.com"],
+/// 	"strategy": "round_robin",
+/// }))
+/// .unwrap();
+/// assert_eq!(urls.select().as_str(), "https://a.example.com/");
+/// assert_eq!(urls.select().as_str(), "https://b.example.com/");
+///
+/// urls.mark_unhealthy(&urls.urls()[0]);
+/// assert_eq!(urls.select().as_str(), "https://b.example.com/");
+/// assert_eq!(urls.select().as_str(), "https://b.example.com/");
+///
+/// let single: BaseUrls = serde_json::from_value(serde_json::json!("https://a.example.com")).unwrap();
+/// assert_eq!(single.strategy(), SelectionStrategy::Priority);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BaseUrls {
+	urls: Vec<BaseUrl>,
+	strategy: SelectionStrategy,
+	state: Arc<State>,
+}
+
+#[derive(Debug)]
+struct State {
+	next: AtomicUsize,
+	unhealthy: Vec<AtomicBool>,
+}
+
+/// Error for a [`BaseUrls`] without URLs
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("At least one base url is required")]
+pub struct EmptyBaseUrls;
+
+impl BaseUrls {
+	/// Deduplicates `urls` keeping the first occurrence. Uses
+	/// [`SelectionStrategy::Priority`], see [`BaseUrls::with_strategy`].
+	pub fn new(urls: impl IntoIterator<Item = BaseUrl>) -> Result<Self, EmptyBaseUrls> {
+		let mut deduplicated: Vec<BaseUrl> = Vec::new();
+		for url in urls {
+			if !deduplicated.contains(&url) {
+				deduplicated.push(url);
+			}
+		}
+		if deduplicated.is_empty() {
+			return Err(EmptyBaseUrls);
+		}
+		let state = State {
+			next: AtomicUsize::new(0),
+			unhealthy: deduplicated.iter().map(|_| AtomicBool::new(false)).collect(),
+		};
+		Ok(Self {
+			urls: deduplicated,
+			strategy: SelectionStrategy::default(),
+			state: Arc::new(state),
+		})
+	}
+
+	/// Sets the selection strategy
+	#[must_use]
+	pub fn with_strategy(mut self, strategy: SelectionStrategy) -> Self {
+		self.strategy = strategy;
+		self
+	}
+
+	/// Selection strategy
+	#[must_use]
+	pub fn strategy(&self) -> SelectionStrategy {
+		self.strategy
+	}
+
+	/// All endpoints in configuration order
+	#[must_use]
+	pub fn urls(&self) -> &[BaseUrl] {
+		&self.urls
+	}
+
+	/// Endpoint to send the next request to
+	#[must_use]
+	pub fn select(&self) -> &BaseUrl {
+		let start = self.start();
+		let n = self.urls.len();
+		(0..n)
+			.map(|i| (start + i) % n)
+			.find(|i| !self.state.unhealthy[*i].load(Ordering::Relaxed))
+			.map_or(&self.urls[start], |i| &self.urls[i])
+	}
+
+	/// All endpoints in the order to try them for a single request: healthy
+	/// ones first, starting with the one [`BaseUrls::select`] would return,
+	/// then the unhealthy ones
+	#[must_use]
+	pub fn candidates(&self) -> Vec<&BaseUrl> {
+		let start = self.start();
+		let n = self.urls.len();
+		let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..n)
+			.map(|i| (start + i) % n)
+			.partition(|i| !self.state.unhealthy[*i].load(Ordering::Relaxed));
+		healthy.extend(unhealthy);
+		healthy.into_iter().map(|i| &self.urls[i]).collect()
+	}
+
+	/// Index of the endpoint the selection starts at
+	fn start(&self) -> usize {
+		let n = self.urls.len();
+		match self.strategy {
+			SelectionStrategy::Priority => 0,
+			SelectionStrategy::RoundRobin => self.state.next.fetch_add(1, Ordering::Relaxed) % n,
+			SelectionStrategy::Random => {
+				usize::try_from(crate::random_u64() % n as u64).unwrap_or_default()
+			}
+		}
+	}
+
+	/// Excludes `url` from selection while there are healthy endpoints. Does
+	/// nothing if `url` is not in the list.
+	pub fn mark_unhealthy(&self, url: &BaseUrl) {
+		self.set_unhealthy(url, true);
+	}
+
+	/// Makes `url` eligible for selection again
+	pub fn mark_healthy(&self, url: &BaseUrl) {
+		self.set_unhealthy(url, false);
+	}
+
+	/// Whether `url` is in the list and not marked unhealthy
+	#[must_use]
+	pub fn is_healthy(&self, url: &BaseUrl) -> bool {
+		self.position(url).is_some_and(|i| !self.state.unhealthy[i].load(Ordering::Relaxed))
+	}
+
+	fn set_unhealthy(&self, url: &BaseUrl, unhealthy: bool) {
+		if let Some(i) = self.position(url) {
+			self.state.unhealthy[i].store(unhealthy, Ordering::Relaxed);
+		}
+	}
+
+	fn position(&self, url: &BaseUrl) -> Option<usize> {
+		self.urls.iter().position(|u| u == url)
+	}
+}
+
+/// Compares URLs and strategy, but not health state
+impl PartialEq for BaseUrls {
+	fn eq(&self, other: &Self) -> bool {
+		self.urls == other.urls && self.strategy == other.strategy
+	}
+}
+
+impl Eq for BaseUrls {}
+
+impl From<BaseUrl> for BaseUrls {
+	fn from(url: BaseUrl) -> Self {
+		Self {
+			urls: vec![url],
+			strategy: SelectionStrategy::default(),
+			state: Arc::new(State {
+				next: AtomicUsize::new(0),
+				unhealthy: vec![AtomicBool::new(false)],
+			}),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a base url, a list of base urls or an object with `urls`")]
+enum BaseUrlsRepr {
+	One(BaseUrl),
+	Many(Vec<BaseUrl>),
+	Full(FullRepr),
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FullRepr {
+	urls: Vec<BaseUrl>,
+	#[serde(default)]
+	strategy: SelectionStrategy,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BaseUrls {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let (urls, strategy) = match BaseUrlsRepr::deserialize(deserializer)? {
+			BaseUrlsRepr::One(url) => (vec![url], SelectionStrategy::default()),
+			BaseUrlsRepr::Many(urls) => (urls, SelectionStrategy::default()),
+			BaseUrlsRepr::Full(FullRepr { urls, strategy }) => (urls, strategy),
+		};
+		Ok(Self::new(urls).map_err(serde::de::Error::custom)?.with_strategy(strategy))
+	}
+}
+
+/// Serializes to the shortest representation that deserializes back
+#[cfg(feature = "serde")]
+impl Serialize for BaseUrls {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		#[derive(Serialize)]
+		struct Full<'a> {
+			urls: &'a [BaseUrl],
+			strategy: SelectionStrategy,
+		}
+		match (&self.urls[..], self.strategy) {
+			([url], SelectionStrategy::Priority) => url.serialize(serializer),
+			(urls, SelectionStrategy::Priority) => urls.serialize(serializer),
+			(urls, strategy) => Full { urls, strategy }.serialize(serializer),
+		}
+	}
+}
+
+#[cfg(all(feature = "serde", feature = "schemars"))]
+impl JsonSchema for BaseUrls {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"BaseUrls".into()
+	}
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		let url = generator.subschema_for::<BaseUrl>();
+		let strategy = generator.subschema_for::<SelectionStrategy>();
+		let list = schemars::json_schema!({"type": "array", "items": url, "minItems": 1});
+		schemars::json_schema!({
+			"anyOf": [
+				url,
+				list,
+				{
+					"type": "object",
+					"properties": {"urls": list, "strategy": strategy},
+					"required": ["urls"],
+					"additionalProperties": false,
+				},
+			],
+		})
+	}
+}
+
+#[cfg(test)]
+fn urls(n: usize) -> Vec<BaseUrl> {
+	(0..n).map(|i| format!("https://{i}.example.com").parse().unwrap()).collect()
+}
+
+#[test]
+fn test_selection() {
+	let list = urls(3);
+	let select = |urls: &BaseUrls, n| -> Vec<_> {
+		(0..n).map(|_| urls.select().host_str().unwrap().to_owned()).collect()
+	};
+
+	let priority = BaseUrls::new(list.clone()).unwrap();
+	assert_eq!(select(&priority, 2), ["0.example.com", "0.example.com"]);
+	priority.mark_unhealthy(&list[0]);
+	assert!(!priority.is_healthy(&list[0]) && priority.is_healthy(&list[1]));
+	assert_eq!(select(&priority, 2), ["1.example.com", "1.example.com"]);
+	assert_eq!(priority.candidates(), [&list[1], &list[2], &list[0]]);
+	// Health state is shared between clones
+	let clone = priority.clone();
+	clone.mark_unhealthy(&list[1]);
+	clone.mark_unhealthy(&list[2]);
+	// Falls back to the regular order if everything is unhealthy
+	assert_eq!(select(&priority, 1), ["0.example.com"]);
+	priority.mark_healthy(&list[2]);
+	assert_eq!(select(&priority, 1), ["2.example.com"]);
+
+	let round_robin =
+		BaseUrls::new(list.clone()).unwrap().with_strategy(SelectionStrategy::RoundRobin);
+	assert_eq!(
+		select(&round_robin, 4),
+		["0.example.com", "1.example.com", "2.example.com", "0.example.com"]
+	);
+	round_robin.mark_unhealthy(&list[2]);
+	assert_eq!(select(&round_robin, 3), ["1.example.com", "0.example.com", "0.example.com"]);
+
+	let random = BaseUrls::new(list.clone()).unwrap().with_strategy(SelectionStrategy::Random);
+	let selected: std::collections::HashSet<_> = select(&random, 100).into_iter().collect();
+	assert_eq!(selected.len(), 3);
+	random.mark_unhealthy(&list[0]);
+	assert!(select(&random, 20).iter().all(|host| host != "0.example.com"));
+	assert_eq!(random.candidates().len(), 3);
+	assert_eq!(random.candidates().last(), Some(&&list[0]));
+}
+
+#[test]
+fn test_new() {
+	let list = urls(2);
+	let deduplicated = BaseUrls::new([
+		list[1].clone(),
+		list[0].clone(),
+		"https://1.example.com/".parse().unwrap(),
+	])
+	.unwrap();
+	assert_eq!(deduplicated.urls(), [list[1].clone(), list[0].clone()]);
+	assert_eq!(BaseUrls::new([]), Err(EmptyBaseUrls));
+	let unknown: BaseUrl = "https://unknown.example.com".parse().unwrap();
+	deduplicated.mark_unhealthy(&unknown);
+	assert!(!deduplicated.is_healthy(&unknown));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+	for (input, strategy, serialized) in [
+		(serde_json::json!("https://a.example.com"), SelectionStrategy::Priority, None),
+		(
+			serde_json::json!(["https://a.example.com/", "https://a.example.com"]),
+			SelectionStrategy::Priority,
+			Some(serde_json::json!("https://a.example.com/")),
+		),
+		(
+			serde_json::json!(["https://a.example.com/", "https://b.example.com/"]),
+			SelectionStrategy::Priority,
+			None,
+		),
+		(
+			serde_json::json!({"urls": ["https://a.example.com/"]}),
+			SelectionStrategy::Priority,
+			Some(serde_json::json!("https://a.example.com/")),
+		),
+		(
+			serde_json::json!({"urls": ["https://a.example.com/"], "strategy": "random"}),
+			SelectionStrategy::Random,
+			None,
+		),
+	] {
+		let urls: BaseUrls = serde_json::from_value(input.clone()).unwrap();
+		assert_eq!(urls.strategy(), strategy);
+		let expected = serialized.unwrap_or_else(|| match input {
+			serde_json::Value::String(url) => serde_json::json!(format!("{url}/")),
+			input => input,
+		});
+		assert_eq!(serde_json::to_value(&urls).unwrap(), expected);
+		assert_eq!(serde_json::from_value::<BaseUrls>(expected).unwrap(), urls);
+	}
+
+	for input in [
+		serde_json::json!([]),
+		serde_json::json!({"urls": []}),
+		serde_json::json!({"urls": ["https://a.example.com"], "strategy": "fastest"}),
+		serde_json::json!({"url": "https://a.example.com"}),
+		serde_json::json!(["not a url"]),
+	] {
+		assert!(serde_json::from_value::<BaseUrls>(input.clone()).is_err(), "{input}");
+	}
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "schemars"))]
+fn test_schemars() {
+	#[derive(schemars::JsonSchema)]
+	struct TestStruct {
+		_a: BaseUrls,
+	}
+	let schema = schemars::schema_for!(TestStruct);
+	let variants = &schema.as_value()["$defs"]["BaseUrls"]["anyOf"];
+	assert_eq!(variants[0]["format"], "uri");
+	assert_eq!(variants[1]["minItems"], 1);
+	assert_eq!(variants[2]["required"], serde_json::json!(["urls"]));
+}
@@ -8,6 +8,12 @@
 //! - `Seconds<std::time::Duration>` - deserializes `u64` as seconds
 //! - `Ms<time::Duration>` - deserializes `i64` into as milliseconds
 //! - `Seconds<time::Duration>` - deserializes `i64` into as seconds
+//! - `Micros<_>` and `Nanos<_>` - same as `Ms<_>` but in microseconds and
+//!   nanoseconds, serialized as `u128`/`i128`
+//! - `Ms<chrono::TimeDelta>`, `Seconds<chrono::TimeDelta>` - same as for
+//!   `time::Duration`, requires `chrono` feature
+//! - `Ms<jiff::SignedDuration>`, `Seconds<jiff::SignedDuration>` - same as for
+//!   `time::Duration`, requires `jiff` feature
 //!
 //! ```
 //! use famedly_rust_utils::duration::Ms;
@@ -16,17 +22,61 @@
 //! 	std::time::Duration::from_millis(567)
 //! );
 //! ```
-use std::time::Duration as StdDuration;
+//!
+//! Every wrapper also has a lowercase module (e.g. [`seconds`], [`ms`]) to use
+//! with `#[serde(with = "...")]` on plain duration fields:
+//! ```
+//! # use std::time::Duration;
+//! #[derive(serde::Deserialize)]
+//! struct Config {
+//! 	#[serde(with = "famedly_rust_utils::duration::seconds")]
+//! 	timeout: Duration,
+//! 	#[serde(with = "famedly_rust_utils::duration::ms::option", default)]
+//! 	delay: Option<Duration>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"timeout": 30}"#).unwrap();
+//! assert_eq!(config.timeout, Duration::from_secs(30));
+//! assert_eq!(config.delay, None);
+//! ```
+//!
+//! With the `schemars` feature the generated schemas carry the unit in
+//! `description` and `x-unit`, and `minimum: 0` for unsigned backings. Use
+//! `Referenced` to get a named definition instead of an inlined schema.
+//!
+//! Any of the above can be wrapped in [`Bounded`] to reject out-of-range
+//! values at deserialization time:
+//! ```
+//! use famedly_rust_utils::duration::{Bounded, Seconds};
+//! type Timeout = Bounded<Seconds<std::time::Duration>, 1, 3600>;
+//! assert!(serde_json::from_str::<Timeout>("30").is_ok());
+//! assert!(serde_json::from_str::<Timeout>("0").is_err());
+//! ```
+use std::{
+	iter::Sum,
+	ops::{Add, AddAssign, Mul, Sub, SubAssign},
+	time::Duration as StdDuration,
+};
 
 #[cfg(feature = "schemars")]
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize, Serializer};
 
+/// Duration wrapper that (de)serializes its value as an integer count of a
+/// fixed unit
+pub trait DurationUnit {
+	/// Human readable name of the unit, e.g. `"seconds"`
+	const UNIT: &'static str;
+	/// Value of the wrapped duration in [`DurationUnit::UNIT`]s, as it would be
+	/// serialized
+	fn units(&self) -> i128;
+}
+
 #[doc(hidden)]
 macro_rules! define_generic_wrapper {
-	($doc:expr, $name:ident: $( $(feature $feat:expr; )? { $t:ty, $deser:expr, $ser:expr }),*) => {
+	($doc:expr, $name:ident($unit:literal): $( $(feature $feat:expr; )? { $t:ty, $deser:expr, $ser:expr, $units:expr }),*) => {
 		#[doc = $doc]
-		#[derive(Debug, PartialEq, Eq, Clone, Default)]
+		#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
 		#[repr(transparent)]
 		pub struct $name<D>(pub D);
 
@@ -62,13 +112,124 @@ macro_rules! define_generic_wrapper {
 			}
 		}
 
+		impl<D: Add<Output = D>> Add for $name<D> {
+			type Output = Self;
+			fn add(self, rhs: Self) -> Self {
+				$name(self.0 + rhs.0)
+			}
+		}
+
+		impl<D: AddAssign> AddAssign for $name<D> {
+			fn add_assign(&mut self, rhs: Self) {
+				self.0 += rhs.0;
+			}
+		}
+
+		impl<D: Sub<Output = D>> Sub for $name<D> {
+			type Output = Self;
+			fn sub(self, rhs: Self) -> Self {
+				$name(self.0 - rhs.0)
+			}
+		}
+
+		impl<D: SubAssign> SubAssign for $name<D> {
+			fn sub_assign(&mut self, rhs: Self) {
+				self.0 -= rhs.0;
+			}
+		}
+
+		impl<D: Mul<u32, Output = D>> Mul<u32> for $name<D> {
+			type Output = Self;
+			fn mul(self, rhs: u32) -> Self {
+				$name(self.0 * rhs)
+			}
+		}
+
+		impl<D: Sum> Sum for $name<D> {
+			fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+				$name(iter.map(|x| x.0).sum())
+			}
+		}
+
+		paste::paste! {
+			#[doc = concat!(
+				"Module to use with `#[serde(with = \"...\")]` to (de)serialize plain durations ",
+				"like [`", stringify!($name), "`] does. Use `", stringify!([<$name:lower>]),
+				"::option` for `Option` fields (combined with `#[serde(default)]`)."
+			)]
+			pub mod [<$name:lower>] {
+				use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+				use super::$name;
+
+				#[allow(missing_docs)]
+				pub fn serialize<T: Copy, S: Serializer>(
+					duration: &T,
+					serializer: S,
+				) -> Result<S::Ok, S::Error>
+				where
+					$name<T>: Serialize,
+				{
+					$name(*duration).serialize(serializer)
+				}
+
+				#[allow(missing_docs)]
+				pub fn deserialize<'de, T, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>
+				where
+					$name<T>: Deserialize<'de>,
+				{
+					$name::<T>::deserialize(deserializer).map($name::into_inner)
+				}
+
+				#[doc = concat!("Same as [`", stringify!([<$name:lower>]), "`](super) but for `Option` fields")]
+				pub mod option {
+					use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+					use super::$name;
+
+					#[allow(missing_docs)]
+					pub fn serialize<T: Copy, S: Serializer>(
+						duration: &Option<T>,
+						serializer: S,
+					) -> Result<S::Ok, S::Error>
+					where
+						$name<T>: Serialize,
+					{
+						duration.map($name).serialize(serializer)
+					}
+
+					#[allow(missing_docs)]
+					pub fn deserialize<'de, T, D: Deserializer<'de>>(
+						deserializer: D,
+					) -> Result<Option<T>, D::Error>
+					where
+						$name<T>: Deserialize<'de>,
+					{
+						Option::<$name<T>>::deserialize(deserializer).map(|x| x.map($name::into_inner))
+					}
+				}
+			}
+		}
+
 		#[cfg(feature = "schemars")]
-		impl<D> JsonSchema for $name<D> {
+		impl<D: DurationRepr> JsonSchema for $name<D> {
 			fn schema_name() -> std::borrow::Cow<'static, str> {
-				concat!("DurationIn", stringify!($name)).into()
+				if D::UNSIGNED {
+					concat!("DurationIn", stringify!($name)).into()
+				} else {
+					concat!("SignedDurationIn", stringify!($name)).into()
+				}
 			}
 			fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
-				schemars::json_schema!({"type": "integer"})
+				let mut schema = schemars::json_schema!({
+					"type": "integer",
+					"description": concat!("Duration in ", $unit),
+					"x-unit": $unit,
+				});
+				if D::UNSIGNED {
+					schema.insert("minimum".to_owned(), 0.into());
+				}
+				schema
 			}
 			fn inline_schema() -> bool {
 				true
@@ -93,6 +254,14 @@ macro_rules! define_generic_wrapper {
 				}
 			}
 
+			$( #[cfg(feature = $feat)] )?
+			impl DurationUnit for $name<$t> {
+				const UNIT: &'static str = $unit;
+				fn units(&self) -> i128 {
+					$units(&self.0)
+				}
+			}
+
 			$( #[cfg(feature = $feat)] )?
 			paste::paste! {
 				#[test]
@@ -118,30 +287,92 @@ macro_rules! define_generic_wrapper {
 					let schema = schemars::schema_for!(TestStruct).as_value()["properties"]["_duration"].clone();
 					assert_eq!(schema["default"], 567);
 					assert_eq!(schema["type"], "integer");
+					assert_eq!(schema["x-unit"], $unit);
+					assert_eq!(schema.get("minimum").is_some(), <$t as DurationRepr>::UNSIGNED);
 				}
 			}
 		)*
 	};
 }
 
+#[cfg(feature = "chrono")]
+use chrono::TimeDelta;
+#[cfg(feature = "jiff")]
+use jiff::SignedDuration;
 #[cfg(feature = "time")]
 use time::Duration as TimeDuration;
 
+#[cfg(any(feature = "chrono", feature = "jiff"))]
+const OUT_OF_RANGE: &str = "duration is out of range";
+
+#[cfg(feature = "schemars")]
+use sealed::DurationRepr;
+
+#[cfg(feature = "schemars")]
+mod sealed {
+	/// Types that duration wrappers have a schema for: the duration types and
+	/// plain integers
+	pub trait DurationRepr {
+		/// Whether the value can't be negative, adding `minimum: 0` to the
+		/// schema
+		const UNSIGNED: bool;
+	}
+
+	macro_rules! impl_duration_repr {
+		($unsigned:literal: $($t:ty),*) => {
+			$(
+				impl DurationRepr for $t {
+					const UNSIGNED: bool = $unsigned;
+				}
+			)*
+		};
+	}
+
+	impl_duration_repr!(true: std::time::Duration, u8, u16, u32, u64, u128, usize);
+	impl_duration_repr!(false: i8, i16, i32, i64, i128, isize);
+	#[cfg(feature = "time")]
+	impl_duration_repr!(false: time::Duration);
+	#[cfg(feature = "chrono")]
+	impl_duration_repr!(false: chrono::TimeDelta);
+	#[cfg(feature = "jiff")]
+	impl_duration_repr!(false: jiff::SignedDuration);
+}
+
 define_generic_wrapper! {
 	"Helper wrapper to use in configs to deserialize durations from seconds",
-	Seconds:
+	Seconds("seconds"):
 
 	{
 		StdDuration,
 		|deserializer| u64::deserialize(deserializer).map(StdDuration::from_secs),
-		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs())
+		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs()),
+		|x: &StdDuration| i128::from(x.as_secs())
 	},
 
 	feature "time";
 	{
 		TimeDuration,
 		|deserializer| i64::deserialize(deserializer).map(TimeDuration::seconds),
-		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_seconds())
+		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_seconds()),
+		|x: &TimeDuration| i128::from(x.whole_seconds())
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			TimeDelta::try_seconds(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i64(x.num_seconds()),
+		|x: &TimeDelta| i128::from(x.num_seconds())
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).map(SignedDuration::from_secs),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i64(x.as_secs()),
+		|x: &SignedDuration| i128::from(x.as_secs())
 	}
 }
 
@@ -168,21 +399,73 @@ impl Seconds<TimeDuration> {
 	}
 }
 
+#[cfg(feature = "chrono")]
+impl Seconds<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(s: u32) -> Self {
+		Self::from_int(s as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(s: i64) -> Self {
+		Seconds(TimeDelta::seconds(s))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Seconds<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(s: u32) -> Self {
+		Self::from_int(s as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(s: i64) -> Self {
+		Seconds(SignedDuration::from_secs(s))
+	}
+}
+
 define_generic_wrapper! {
 	"Helper wrapper to use in configs to deserialize durations from minutes",
-	Minutes:
+	Minutes("minutes"):
 
 	{
 		StdDuration,
 		|deserializer| u64::deserialize(deserializer).map(|x| StdDuration::from_secs(x * 60)),
-		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs() / 60)
+		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs() / 60),
+		|x: &StdDuration| i128::from(x.as_secs() / 60)
 	},
 
 	feature "time";
 	{
 		TimeDuration,
 		|deserializer| i64::deserialize(deserializer).map(TimeDuration::minutes),
-		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_minutes())
+		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_minutes()),
+		|x: &TimeDuration| i128::from(x.whole_minutes())
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			TimeDelta::try_minutes(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i64(x.num_minutes()),
+		|x: &TimeDelta| i128::from(x.num_minutes())
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			SignedDuration::try_from_mins(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i64(x.as_mins()),
+		|x: &SignedDuration| i128::from(x.as_mins())
 	}
 }
 
@@ -209,21 +492,73 @@ impl Minutes<TimeDuration> {
 	}
 }
 
+#[cfg(feature = "chrono")]
+impl Minutes<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(m: u32) -> Self {
+		Self::from_int(m as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(m: i64) -> Self {
+		Minutes(TimeDelta::minutes(m))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Minutes<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(m: u32) -> Self {
+		Self::from_int(m as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(m: i64) -> Self {
+		Minutes(SignedDuration::from_mins(m))
+	}
+}
+
 define_generic_wrapper! {
 	"Helper wrapper to use in configs to deserialize durations from hours",
-	Hours:
+	Hours("hours"):
 
 	{
 		StdDuration,
 		|deserializer| u64::deserialize(deserializer).map(|x| StdDuration::from_secs(x * 60 * 60)),
-		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs() / 60 / 60)
+		|serializer: S, x: &StdDuration| serializer.serialize_u64(x.as_secs() / 60 / 60),
+		|x: &StdDuration| i128::from(x.as_secs() / 60 / 60)
 	},
 
 	feature "time";
 	{
 		TimeDuration,
 		|deserializer| i64::deserialize(deserializer).map(TimeDuration::hours),
-		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_hours())
+		|serializer: S, x: &TimeDuration| serializer.serialize_i64(x.whole_hours()),
+		|x: &TimeDuration| i128::from(x.whole_hours())
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			TimeDelta::try_hours(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i64(x.num_hours()),
+		|x: &TimeDelta| i128::from(x.num_hours())
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			SignedDuration::try_from_hours(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i64(x.as_hours()),
+		|x: &SignedDuration| i128::from(x.as_hours())
 	}
 }
 
@@ -250,21 +585,71 @@ impl Hours<TimeDuration> {
 	}
 }
 
+#[cfg(feature = "chrono")]
+impl Hours<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(h: u32) -> Self {
+		Self::from_int(h as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(h: i64) -> Self {
+		Hours(TimeDelta::hours(h))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Hours<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(h: u32) -> Self {
+		Self::from_int(h as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(h: i64) -> Self {
+		Hours(SignedDuration::from_hours(h))
+	}
+}
+
 define_generic_wrapper! {
 	"Helper wrapper to use in configs to deserialize durations from milliseconds",
-	Ms:
+	Ms("milliseconds"):
 
 	{
 		StdDuration,
 		|deserializer| u64::deserialize(deserializer).map(StdDuration::from_millis),
-		|serializer: S, x: &StdDuration| serializer.serialize_u128(x.as_millis())
+		|serializer: S, x: &StdDuration| serializer.serialize_u128(x.as_millis()),
+		|x: &StdDuration| i128::try_from(x.as_millis()).unwrap_or(i128::MAX)
 	},
 
 	feature "time";
 	{
 		TimeDuration,
 		|deserializer| i64::deserialize(deserializer).map(TimeDuration::milliseconds),
-		|serializer: S, x: &TimeDuration| serializer.serialize_i128(x.whole_milliseconds())
+		|serializer: S, x: &TimeDuration| serializer.serialize_i128(x.whole_milliseconds()),
+		|x: &TimeDuration| x.whole_milliseconds()
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).and_then(|x| {
+			TimeDelta::try_milliseconds(x).ok_or_else(|| serde::de::Error::custom(OUT_OF_RANGE))
+		}),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i64(x.num_milliseconds()),
+		|x: &TimeDelta| i128::from(x.num_milliseconds())
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).map(SignedDuration::from_millis),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i128(x.as_millis()),
+		|x: &SignedDuration| x.as_millis()
 	}
 }
 
@@ -290,3 +675,585 @@ impl Ms<TimeDuration> {
 		Ms(TimeDuration::milliseconds(ms))
 	}
 }
+
+#[cfg(feature = "chrono")]
+impl Ms<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ms: u32) -> Self {
+		Self::from_int(ms as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(ms: i64) -> Self {
+		Ms(TimeDelta::milliseconds(ms))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Ms<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ms: u32) -> Self {
+		Self::from_int(ms as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(ms: i64) -> Self {
+		Ms(SignedDuration::from_millis(ms))
+	}
+}
+
+define_generic_wrapper! {
+	"Helper wrapper to use in configs to deserialize durations from microseconds",
+	Micros("microseconds"):
+
+	{
+		StdDuration,
+		|deserializer| u64::deserialize(deserializer).map(StdDuration::from_micros),
+		|serializer: S, x: &StdDuration| serializer.serialize_u128(x.as_micros()),
+		|x: &StdDuration| i128::try_from(x.as_micros()).unwrap_or(i128::MAX)
+	},
+
+	feature "time";
+	{
+		TimeDuration,
+		|deserializer| i64::deserialize(deserializer).map(TimeDuration::microseconds),
+		|serializer: S, x: &TimeDuration| serializer.serialize_i128(x.whole_microseconds()),
+		|x: &TimeDuration| x.whole_microseconds()
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).map(TimeDelta::microseconds),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i128(chrono_whole_micros(x)),
+		chrono_whole_micros
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).map(SignedDuration::from_micros),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i128(x.as_micros()),
+		|x: &SignedDuration| x.as_micros()
+	}
+}
+
+impl Micros<StdDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(us: u64) -> Self {
+		Micros(StdDuration::from_micros(us))
+	}
+}
+
+#[cfg(feature = "time")]
+impl Micros<TimeDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(us: u32) -> Self {
+		Self::from_int(us as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(us: i64) -> Self {
+		Micros(TimeDuration::microseconds(us))
+	}
+}
+
+#[cfg(feature = "chrono")]
+impl Micros<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(us: u32) -> Self {
+		Self::from_int(us as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(us: i64) -> Self {
+		Micros(TimeDelta::microseconds(us))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Micros<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(us: u32) -> Self {
+		Self::from_int(us as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(us: i64) -> Self {
+		Micros(SignedDuration::from_micros(us))
+	}
+}
+
+define_generic_wrapper! {
+	"Helper wrapper to use in configs to deserialize durations from nanoseconds",
+	Nanos("nanoseconds"):
+
+	{
+		StdDuration,
+		|deserializer| u64::deserialize(deserializer).map(StdDuration::from_nanos),
+		|serializer: S, x: &StdDuration| serializer.serialize_u128(x.as_nanos()),
+		|x: &StdDuration| i128::try_from(x.as_nanos()).unwrap_or(i128::MAX)
+	},
+
+	feature "time";
+	{
+		TimeDuration,
+		|deserializer| i64::deserialize(deserializer).map(TimeDuration::nanoseconds),
+		|serializer: S, x: &TimeDuration| serializer.serialize_i128(x.whole_nanoseconds()),
+		|x: &TimeDuration| x.whole_nanoseconds()
+	},
+
+	feature "chrono";
+	{
+		TimeDelta,
+		|deserializer| i64::deserialize(deserializer).map(TimeDelta::nanoseconds),
+		|serializer: S, x: &TimeDelta| serializer.serialize_i128(chrono_whole_nanos(x)),
+		chrono_whole_nanos
+	},
+
+	feature "jiff";
+	{
+		SignedDuration,
+		|deserializer| i64::deserialize(deserializer).map(SignedDuration::from_nanos),
+		|serializer: S, x: &SignedDuration| serializer.serialize_i128(x.as_nanos()),
+		|x: &SignedDuration| x.as_nanos()
+	}
+}
+
+impl Nanos<StdDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ns: u64) -> Self {
+		Nanos(StdDuration::from_nanos(ns))
+	}
+}
+
+#[cfg(feature = "time")]
+impl Nanos<TimeDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ns: u32) -> Self {
+		Self::from_int(ns as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(ns: i64) -> Self {
+		Nanos(TimeDuration::nanoseconds(ns))
+	}
+}
+
+#[cfg(feature = "chrono")]
+impl Nanos<TimeDelta> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ns: u32) -> Self {
+		Self::from_int(ns as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(ns: i64) -> Self {
+		Nanos(TimeDelta::nanoseconds(ns))
+	}
+}
+
+#[cfg(feature = "jiff")]
+impl Nanos<SignedDuration> {
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_uint(ns: u32) -> Self {
+		Self::from_int(ns as i64)
+	}
+
+	#[allow(missing_docs)]
+	#[must_use]
+	pub const fn from_int(ns: i64) -> Self {
+		Nanos(SignedDuration::from_nanos(ns))
+	}
+}
+
+/// [`TimeDelta::num_microseconds`] overflows `i64` for large durations
+#[cfg(feature = "chrono")]
+fn chrono_whole_micros(x: &TimeDelta) -> i128 {
+	i128::from(x.num_seconds()) * 1_000_000 + i128::from(x.subsec_nanos() / 1_000)
+}
+
+/// [`TimeDelta::num_nanoseconds`] overflows `i64` for large durations
+#[cfg(feature = "chrono")]
+fn chrono_whole_nanos(x: &TimeDelta) -> i128 {
+	i128::from(x.num_seconds()) * 1_000_000_000 + i128::from(x.subsec_nanos())
+}
+
+/// Lossless conversions from coarser to finer units, e.g.
+/// `Seconds::from(Minutes(d))`. The wrapped duration is left untouched, only
+/// the unit used for (de)serialization changes.
+macro_rules! impl_unit_conversions {
+	($($from:ident => $($to:ident),+;)*) => {
+		$($(
+			impl<D> From<$from<D>> for $to<D> {
+				fn from(duration: $from<D>) -> Self {
+					$to(duration.0)
+				}
+			}
+		)+)*
+	};
+}
+
+impl_unit_conversions! {
+	Hours => Minutes, Seconds, Ms, Micros, Nanos;
+	Minutes => Seconds, Ms, Micros, Nanos;
+	Seconds => Ms, Micros, Nanos;
+	Ms => Micros, Nanos;
+	Micros => Nanos;
+}
+
+/// Duration wrapper with inclusive bounds `MIN..=MAX`, expressed in the unit of
+/// the inner wrapper `W` (see [`DurationUnit`]). Values outside of the bounds
+/// are rejected during deserialization.
+/// ```
+/// # use famedly_rust_utils::duration::{Bounded, Minutes};
+/// # use std::time::Duration;
+/// let x: Bounded<Minutes<Duration>, 1, 60> =
+/// 	serde_json::from_str("5").unwrap();
+/// assert_eq!(**x, Duration::from_secs(5 * 60));
+/// assert!(serde_json::from_str::<Bounded<Minutes<Duration>, 1, 60>>("61")
+/// 	.is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(transparent)]
+pub struct Bounded<W, const MIN: u64, const MAX: u64 = { u64::MAX }>(W);
+
+impl<W: DurationUnit, const MIN: u64, const MAX: u64> Bounded<W, MIN, MAX> {
+	const VALID_BOUNDS: () = assert!(MIN <= MAX, "`MIN` must not be greater than `MAX`");
+
+	/// Wraps `duration`, failing if it is outside of `MIN..=MAX`
+	pub fn new(duration: W) -> Result<Self, DurationOutOfBounds> {
+		let () = Self::VALID_BOUNDS;
+		let value = duration.units();
+		if (i128::from(MIN)..=i128::from(MAX)).contains(&value) {
+			Ok(Bounded(duration))
+		} else {
+			Err(DurationOutOfBounds { value, min: MIN, max: MAX, unit: W::UNIT })
+		}
+	}
+}
+
+impl<W, const MIN: u64, const MAX: u64> Bounded<W, MIN, MAX> {
+	#[allow(missing_docs)]
+	pub fn into_inner(self) -> W {
+		self.0
+	}
+}
+
+impl<W, const MIN: u64, const MAX: u64> std::ops::Deref for Bounded<W, MIN, MAX> {
+	type Target = W;
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<W, const MIN: u64, const MAX: u64> AsRef<W> for Bounded<W, MIN, MAX> {
+	fn as_ref(&self) -> &W {
+		&self.0
+	}
+}
+
+impl<W: std::fmt::Display, const MIN: u64, const MAX: u64> std::fmt::Display
+	for Bounded<W, MIN, MAX>
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+		self.0.fmt(f)
+	}
+}
+
+impl<'de, W, const MIN: u64, const MAX: u64> Deserialize<'de> for Bounded<W, MIN, MAX>
+where
+	W: Deserialize<'de> + DurationUnit,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		Self::new(W::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
+impl<W: Serialize, const MIN: u64, const MAX: u64> Serialize for Bounded<W, MIN, MAX> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.0.serialize(serializer)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl<W: JsonSchema, const MIN: u64, const MAX: u64> JsonSchema for Bounded<W, MIN, MAX> {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		format!("{}Between{MIN}And{MAX}", W::schema_name()).into()
+	}
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		let mut schema = W::json_schema(generator);
+		schema.insert("minimum".to_owned(), MIN.into());
+		if MAX != u64::MAX {
+			schema.insert("maximum".to_owned(), MAX.into());
+		}
+		schema
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Schema helper that emits the schema of `W` as a named definition referenced
+/// via `$ref` instead of inlining it, so generated config docs get a single
+/// stable entry per duration type. Meant to be used with `#[schemars(with)]`:
+/// ```
+/// # use famedly_rust_utils::duration::{Referenced, Seconds};
+/// # use std::time::Duration;
+/// #[derive(schemars::JsonSchema)]
+/// struct Config {
+/// 	#[schemars(with = "Referenced<Seconds<Duration>>")]
+/// 	timeout: Seconds<Duration>,
+/// }
+///
+/// let schema = schemars::schema_for!(Config);
+/// assert_eq!(
+/// 	schema.as_value()["properties"]["timeout"]["$ref"],
+/// 	"#/$defs/DurationInSeconds"
+/// );
+/// ```
+#[cfg(feature = "schemars")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+#[repr(transparent)]
+pub struct Referenced<W>(pub W);
+
+#[cfg(feature = "schemars")]
+impl<W: JsonSchema> JsonSchema for Referenced<W> {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		W::schema_name()
+	}
+	fn schema_id() -> std::borrow::Cow<'static, str> {
+		W::schema_id()
+	}
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		W::json_schema(generator)
+	}
+	fn inline_schema() -> bool {
+		false
+	}
+}
+
+/// Error returned when a duration is outside of the bounds of [`Bounded`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationOutOfBounds {
+	/// The rejected value in `unit`s
+	pub value: i128,
+	/// Inclusive lower bound
+	pub min: u64,
+	/// Inclusive upper bound
+	pub max: u64,
+	/// Unit of `value`, `min` and `max`
+	pub unit: &'static str,
+}
+
+impl std::fmt::Display for DurationOutOfBounds {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+		write!(
+			f,
+			"duration of {} {} is out of bounds, expected between {} and {} {}",
+			self.value, self.unit, self.min, self.max, self.unit
+		)
+	}
+}
+
+impl std::error::Error for DurationOutOfBounds {}
+
+#[test]
+fn test_arithmetic() {
+	let a = Seconds::<StdDuration>::from_uint(30);
+	let b = Seconds::<StdDuration>::from_uint(12);
+	assert_eq!(a + b, Seconds::<StdDuration>::from_uint(42));
+	assert_eq!(a - b, Seconds::<StdDuration>::from_uint(18));
+	assert_eq!(a * 3, Seconds::<StdDuration>::from_uint(90));
+	assert_eq!([a, b, b].into_iter().sum::<Seconds<_>>(), Seconds::<StdDuration>::from_uint(54));
+	assert!(b < a);
+	assert_eq!([a, b].into_iter().max(), Some(a));
+
+	let mut c = a;
+	c += b;
+	c -= Seconds::<StdDuration>::from_uint(2);
+	assert_eq!(c, Seconds::<StdDuration>::from_uint(40));
+}
+
+#[test]
+fn test_unit_conversions() {
+	assert_eq!(
+		Seconds::from(Minutes::<StdDuration>::from_uint(2)),
+		Seconds::<StdDuration>::from_uint(120)
+	);
+	assert_eq!(
+		Ms::from(Hours::<StdDuration>::from_uint(1)),
+		Ms::<StdDuration>::from_uint(3_600_000)
+	);
+	let m: Minutes<StdDuration> = Hours::<StdDuration>::from_uint(2).into();
+	assert_eq!(serde_json::to_value(m).unwrap(), serde_json::json!(120));
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_time_arithmetic() {
+	let a = Minutes::<TimeDuration>::from_int(5);
+	let b = Minutes::<TimeDuration>::from_int(7);
+	assert_eq!(a - b, Minutes::<TimeDuration>::from_int(-2));
+	assert_eq!(a * 2, Minutes::<TimeDuration>::from_int(10));
+	assert_eq!(Seconds::from(a + b), Seconds::<TimeDuration>::from_int(720));
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_out_of_range() {
+	let err = serde_json::from_value::<Hours<TimeDelta>>(serde_json::json!(i64::MAX)).unwrap_err();
+	assert_eq!(err.to_string(), OUT_OF_RANGE);
+}
+
+#[test]
+fn test_sub_ms_units() {
+	assert_eq!(*Micros::<StdDuration>::from_uint(1_500), StdDuration::from_micros(1_500));
+	let x: Nanos<StdDuration> = Micros::<StdDuration>::from_uint(2).into();
+	assert_eq!(x.units(), 2_000);
+	// doesn't fit into `u64` nanoseconds
+	let x = Nanos(StdDuration::from_secs(u64::MAX / 1_000));
+	assert_eq!(x.units(), i128::from(u64::MAX / 1_000) * 1_000_000_000);
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_sub_ms_units() {
+	let x = Nanos::<TimeDelta>::from_int(-1_500_000_001);
+	assert_eq!(x.units(), -1_500_000_001);
+	assert_eq!(serde_json::to_value(x).unwrap(), serde_json::json!(-1_500_000_001_i64));
+	assert_eq!(Micros(TimeDelta::milliseconds(-2_500)).units(), -2_500_000);
+}
+
+#[test]
+fn test_with_modules() {
+	#[derive(Debug, PartialEq, Deserialize, Serialize)]
+	struct TestStruct {
+		#[serde(with = "minutes")]
+		a: StdDuration,
+		#[serde(with = "ms::option", default)]
+		b: Option<StdDuration>,
+		#[serde(with = "nanos::option", default)]
+		c: Option<StdDuration>,
+	}
+	let json = serde_json::json!({"a": 2, "b": 1500});
+	let x: TestStruct = serde_json::from_value(json).unwrap();
+	assert_eq!(
+		x,
+		TestStruct {
+			a: StdDuration::from_secs(120),
+			b: Some(StdDuration::from_millis(1500)),
+			c: None
+		}
+	);
+	assert_eq!(
+		serde_json::to_value(&x).unwrap(),
+		serde_json::json!({"a": 2, "b": 1500, "c": null})
+	);
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_time_with_modules() {
+	#[derive(Debug, PartialEq, Deserialize, Serialize)]
+	struct TestStruct {
+		#[serde(with = "hours")]
+		a: TimeDuration,
+	}
+	let x: TestStruct = serde_json::from_value(serde_json::json!({"a": -2})).unwrap();
+	assert_eq!(x.a, TimeDuration::hours(-2));
+	assert_eq!(serde_json::to_value(&x).unwrap(), serde_json::json!({"a": -2}));
+}
+
+#[test]
+fn test_bounded_serde() {
+	type Timeout = Bounded<Seconds<StdDuration>, 1, 3600>;
+	let x: Timeout = serde_json::from_value(serde_json::json!(30)).unwrap();
+	assert_eq!(**x, StdDuration::from_secs(30));
+	assert_eq!(serde_json::to_value(x).unwrap(), serde_json::json!(30));
+	assert!(serde_json::from_value::<Timeout>(serde_json::json!(1)).is_ok());
+	assert!(serde_json::from_value::<Timeout>(serde_json::json!(3600)).is_ok());
+	let err = serde_json::from_value::<Timeout>(serde_json::json!(0)).unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"duration of 0 seconds is out of bounds, expected between 1 and 3600 seconds"
+	);
+	assert!(serde_json::from_value::<Timeout>(serde_json::json!(3601)).is_err());
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_bounded_negative() {
+	type Delay = Bounded<Ms<TimeDuration>, 0, 1000>;
+	assert!(serde_json::from_value::<Delay>(serde_json::json!(1000)).is_ok());
+	assert!(serde_json::from_value::<Delay>(serde_json::json!(-1)).is_err());
+}
+
+#[test]
+#[cfg(all(feature = "schemars", feature = "time"))]
+fn test_referenced_schemars() {
+	#[derive(schemars::JsonSchema)]
+	struct TestStruct {
+		#[schemars(with = "Referenced<Seconds<StdDuration>>")]
+		_a: Seconds<StdDuration>,
+		_b: Referenced<Seconds<StdDuration>>,
+		_c: Referenced<Seconds<TimeDuration>>,
+	}
+	let schema = schemars::schema_for!(TestStruct);
+	let schema = schema.as_value();
+	assert_eq!(schema["properties"]["_a"]["$ref"], "#/$defs/DurationInSeconds");
+	assert_eq!(schema["properties"]["_b"]["$ref"], "#/$defs/DurationInSeconds");
+	assert_eq!(schema["properties"]["_c"]["$ref"], "#/$defs/SignedDurationInSeconds");
+	assert_eq!(schema["$defs"]["DurationInSeconds"]["minimum"], 0);
+	assert_eq!(schema["$defs"]["SignedDurationInSeconds"]["x-unit"], "seconds");
+}
+
+#[test]
+#[cfg(feature = "schemars")]
+fn test_bounded_schemars() {
+	#[derive(schemars::JsonSchema)]
+	struct TestStruct {
+		_bounded: Bounded<Hours<StdDuration>, 1, 24>,
+		_unbounded: Bounded<Hours<StdDuration>, 1>,
+	}
+	let schema = schemars::schema_for!(TestStruct);
+	let props = &schema.as_value()["properties"];
+	assert_eq!(props["_bounded"]["type"], "integer");
+	assert_eq!(props["_bounded"]["minimum"], 1);
+	assert_eq!(props["_bounded"]["maximum"], 24);
+	assert_eq!(props["_unbounded"]["minimum"], 1);
+	assert!(props["_unbounded"].get("maximum").is_none());
+}
+
+#[test]
+#[cfg(feature = "schemars")]
+fn test_schemars_other_backing() {
+	let schema = schemars::schema_for!(Seconds<u32>);
+	assert_eq!(schema.as_value()["type"], "integer");
+	assert_eq!(schema.as_value()["x-unit"], "seconds");
+	assert_eq!(schema.as_value()["minimum"], 0);
+	let schema = schemars::schema_for!(Ms<i64>);
+	assert_eq!(schema.as_value()["x-unit"], "milliseconds");
+	assert!(schema.as_value().get("minimum").is_none());
+}
@@ -13,37 +13,64 @@
 //!
 //! See [`GenericCombinators`] for some useful generic methods.
 //!
-//! See [`LevelFilter`], [`BaseUrl`] and [`duration`] for useful wrapper types
-//! to use in your `serde`-based configs.
+//! See [`LevelFilter`], [`BaseUrl`], [`BaseUrls`], [`duration`] and
+//! [`timestamp`] for useful wrapper types to use in your `serde`-based configs.
+//! [`system_time`] provides RFC3339 timestamps without the `time` dependency,
+//! [`time_range`] provides time windows and [`schedule`] recurring schedules
+//! for periodic jobs.
 //!
 //! Enable `schemars` feature to get [`schemars::JsonSchema`] impls for
 //! "config-helper" types to generate config schemas (for documentation and
 //! validation purposes).
 //!
+//! Enable `postgres` feature to get [`postgres_types`] impls to store
+//! timestamps, durations and base urls in Postgres.
+//!
 //! See [`config::parse_config`] for an opinionated figment-based
 //! configuration parser.
 //!
 //! See [`reqwest::ReqwestErrorWithBody`] for a reqwest error that
 //! keeps the body of the error instead of discarding it in favor of
-//! the status code.
+//! the status code, [`reqwest::HttpClientConfig`] for building a client
+//! from config and [`reqwest::SendWithRetry`] for retrying transient failures.
 
 #[cfg(feature = "base_url")]
 mod base_url;
+#[cfg(feature = "base_url")]
+mod base_urls;
 #[cfg(feature = "config")]
 pub mod config;
 #[cfg(feature = "serde")]
 pub mod duration;
 #[cfg(feature = "level_filter")]
 mod level_filter;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "base_url")]
+mod query;
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
 #[cfg(all(feature = "time", feature = "serde"))]
+pub mod schedule;
+#[cfg(feature = "serde")]
+pub mod system_time;
+#[cfg(all(feature = "time", feature = "serde"))]
+pub mod time_range;
+#[cfg(all(feature = "time", feature = "serde"))]
 pub mod timestamp;
 
 #[cfg(feature = "base_url")]
-pub use base_url::{BaseUrl, BaseUrlParseError};
+pub use base_url::{
+	BaseUrl, BaseUrlParseError, BaseUrlPathError, BaseUrlPolicy, HttpsBaseUrl, HttpsOnly,
+	PublicHttpsBaseUrl, PublicHttpsOnly, RedactedBaseUrl, RedactedUrl, StrictBaseUrl, UrlBuilder,
+	REDACTED_QUERY_PARAMS,
+};
+#[cfg(feature = "base_url")]
+pub use base_urls::{BaseUrls, EmptyBaseUrls, SelectionStrategy};
 #[cfg(feature = "level_filter")]
 pub use level_filter::LevelFilter;
+#[cfg(feature = "base_url")]
+pub use query::QueryEncodeError;
 
 /// Generic combinators on polymorphic unconstrained types that `std` lacks.
 ///
@@ -265,6 +292,20 @@ impl<X> schemars::JsonSchema for AsString<X> {
 	}
 }
 
+/// Random number for retry jitter and endpoint selection. [`RandomState`] is
+/// seeded randomly once per thread and increments its keys for every instance,
+/// so hashing nothing with a fresh one still gives well-mixed, unpredictable
+/// enough values for spreading load without depending on `rand`. Not suitable
+/// for anything security related.
+///
+/// [`RandomState`]: std::collections::hash_map::RandomState
+#[cfg(any(feature = "reqwest", feature = "base_url"))]
+fn random_u64() -> u64 {
+	use std::hash::{BuildHasher, Hasher};
+
+	std::collections::hash_map::RandomState::new().build_hasher().finish()
+}
+
 #[test]
 fn test_ignore() -> Result<(), String> {
 	let some_res: Result<u8, String> = Ok(3);
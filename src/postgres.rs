@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! [`postgres_types`] impls to store helper types in Postgres (usable with
+//! `tokio-postgres`, `deadpool-postgres` and friends):
+//!
+//! - `Timestamp` and `UtcTimestamp` as `timestamptz`, requires `time` and
+//!   `serde` features. Note that Postgres stores microsecond precision.
+//! - Duration wrappers as `int8` counting the wrapper unit, e.g. `Seconds<_>`
+//!   as seconds, or as `interval`, requires `serde` feature. Durations that
+//!   aren't a whole number of the wrapper unit are rejected instead of being
+//!   truncated. Intervals are encoded without days and months. When decoding, a
+//!   day counts as 24 hours, and intervals with months or that aren't a whole
+//!   number of the wrapper unit are rejected.
+//! - `BaseUrl` as `text`, requires `base_url` feature
+#![cfg(any(feature = "serde", feature = "base_url"))]
+
+use std::error::Error;
+
+use bytes::BytesMut;
+#[cfg(feature = "serde")]
+use postgres_types::accepts;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+#[cfg(feature = "serde")]
+mod duration {
+	use bytes::BufMut;
+	use serde::{de::value::I64Deserializer, Deserialize};
+
+	use super::*;
+	use crate::duration::{DurationUnit, Hours, Micros, Minutes, Ms, Nanos, Seconds};
+
+	const NANOS_PER_MICRO: i128 = 1_000;
+	const MICROS_PER_DAY: i128 = 86_400_000_000;
+
+	/// Encodes `nanos` as an `interval` of microseconds
+	fn interval_to_sql(nanos: i128, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+		if nanos % NANOS_PER_MICRO != 0 {
+			return Err("Postgres intervals have microsecond precision".into());
+		}
+		out.put_i64(i64::try_from(nanos / NANOS_PER_MICRO)?);
+		// Days and months
+		out.put_i32(0);
+		out.put_i32(0);
+		Ok(IsNull::No)
+	}
+
+	/// Decodes an `interval` into nanoseconds, counting a day as 24 hours
+	fn interval_from_sql(raw: &[u8]) -> Result<i128, BoxError> {
+		let raw: [u8; 16] = raw.try_into().map_err(|_| "invalid interval length")?;
+		let [m0, m1, m2, m3, m4, m5, m6, m7, d0, d1, d2, d3, n0, n1, n2, n3] = raw;
+		let micros = i64::from_be_bytes([m0, m1, m2, m3, m4, m5, m6, m7]);
+		let days = i32::from_be_bytes([d0, d1, d2, d3]);
+		if i32::from_be_bytes([n0, n1, n2, n3]) != 0 {
+			return Err("intervals with months have no fixed duration".into());
+		}
+		Ok((i128::from(micros) + i128::from(days) * MICROS_PER_DAY) * NANOS_PER_MICRO)
+	}
+
+	/// Converts `nanos` into a count of units of `unit_nanos` each
+	fn whole_units(nanos: i128, unit_nanos: i128, unit: &str) -> Result<i64, BoxError> {
+		if nanos % unit_nanos != 0 {
+			return Err(format!("interval is not a whole number of {unit}").into());
+		}
+		Ok(i64::try_from(nanos / unit_nanos)?)
+	}
+
+	macro_rules! impl_duration_sql {
+		($($name:ident($unit:literal, $nanos:literal)),*) => {
+			$(
+				impl<D> ToSql for $name<D>
+				where
+					$name<D>: DurationUnit + PartialEq + std::fmt::Debug + for<'de> Deserialize<'de>,
+				{
+					fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+						let units = i64::try_from(self.units())?;
+						// `units` truncates, so check that it converts back to the same duration
+						let exact = Self::deserialize(I64Deserializer::<serde::de::value::Error>::new(units));
+						if exact.ok().as_ref() != Some(self) {
+							return Err(format!("{self:?} is not a whole number of {}", $unit).into());
+						}
+						if *ty == Type::INTERVAL {
+							let nanos =
+								i128::from(units).checked_mul($nanos).ok_or("duration is out of range")?;
+							return interval_to_sql(nanos, out);
+						}
+						units.to_sql(ty, out)
+					}
+					accepts!(INT8, INTERVAL);
+					to_sql_checked!();
+				}
+
+				impl<'a, D> FromSql<'a> for $name<D>
+				where
+					$name<D>: Deserialize<'a>,
+				{
+					fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+						let units = if *ty == Type::INTERVAL {
+							whole_units(interval_from_sql(raw)?, $nanos, $unit)?
+						} else {
+							i64::from_sql(ty, raw)?
+						};
+						Ok(Self::deserialize(I64Deserializer::<serde::de::value::Error>::new(units))?)
+					}
+					accepts!(INT8, INTERVAL);
+				}
+			)*
+		};
+	}
+
+	impl_duration_sql!(
+		Seconds("seconds", 1_000_000_000),
+		Minutes("minutes", 60_000_000_000),
+		Hours("hours", 3_600_000_000_000),
+		Ms("milliseconds", 1_000_000),
+		Micros("microseconds", 1_000),
+		Nanos("nanoseconds", 1)
+	);
+}
+
+#[cfg(all(feature = "time", feature = "serde"))]
+mod timestamp {
+	use time::OffsetDateTime;
+
+	use super::*;
+	use crate::timestamp::{Timestamp, UtcTimestamp};
+
+	impl ToSql for Timestamp {
+		fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+			AsRef::<OffsetDateTime>::as_ref(self).to_sql(ty, out)
+		}
+		accepts!(TIMESTAMPTZ);
+		to_sql_checked!();
+	}
+
+	impl<'a> FromSql<'a> for Timestamp {
+		fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+			OffsetDateTime::from_sql(ty, raw).map(Self::from)
+		}
+		accepts!(TIMESTAMPTZ);
+	}
+
+	impl<const DIGITS: u8> ToSql for UtcTimestamp<DIGITS> {
+		fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+			AsRef::<OffsetDateTime>::as_ref(self).to_sql(ty, out)
+		}
+		accepts!(TIMESTAMPTZ);
+		to_sql_checked!();
+	}
+
+	impl<'a, const DIGITS: u8> FromSql<'a> for UtcTimestamp<DIGITS> {
+		fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+			OffsetDateTime::from_sql(ty, raw).map(Self::from)
+		}
+		accepts!(TIMESTAMPTZ);
+	}
+}
+
+#[cfg(feature = "base_url")]
+mod base_url {
+	use super::*;
+	use crate::BaseUrl;
+
+	impl ToSql for BaseUrl {
+		fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+			self.as_str().to_sql(ty, out)
+		}
+		fn accepts(ty: &Type) -> bool {
+			<&str as ToSql>::accepts(ty)
+		}
+		to_sql_checked!();
+	}
+
+	impl<'a> FromSql<'a> for BaseUrl {
+		fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+			Ok(<&str>::from_sql(ty, raw)?.parse()?)
+		}
+		fn accepts(ty: &Type) -> bool {
+			<&str as FromSql>::accepts(ty)
+		}
+	}
+}
+
+/// Encodes `x` the way it is sent to Postgres and decodes it back
+#[cfg(test)]
+fn roundtrip<T: ToSql + for<'a> FromSql<'a>>(x: &T, ty: &Type) -> Result<T, BoxError> {
+	let mut buf = BytesMut::new();
+	x.to_sql_checked(ty, &mut buf)?;
+	T::from_sql(ty, &buf)
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_duration_sql() {
+	use std::time::Duration;
+
+	use crate::duration::{Minutes, Ms, Seconds};
+
+	let x = Minutes::<Duration>::from_uint(90);
+	assert_eq!(roundtrip(&x, &Type::INT8).unwrap(), x);
+	let mut buf = BytesMut::new();
+	x.to_sql_checked(&Type::INT8, &mut buf).unwrap();
+	assert_eq!(i64::from_sql(&Type::INT8, &buf).unwrap(), 90);
+
+	assert_eq!(roundtrip(&Ms::<Duration>::from_uint(1500), &Type::INT8).unwrap().as_millis(), 1500);
+	assert!(roundtrip(&Seconds::<Duration>::from_uint(1), &Type::TEXT).is_err());
+	assert!(Seconds::<Duration>::from_sql(&Type::INT8, &(-1_i64).to_be_bytes()).is_err());
+
+	let x = Seconds(Duration::from_millis(1500));
+	assert!(roundtrip(&x, &Type::INT8).is_err());
+	assert!(roundtrip(&x, &Type::INTERVAL).is_err());
+}
+
+/// Values as sent by Postgres in binary format
+#[test]
+#[cfg(feature = "serde")]
+fn test_postgres_wire_format() {
+	use std::time::Duration;
+
+	use crate::duration::{Ms, Seconds};
+
+	// `42::int8`
+	let raw = [0, 0, 0, 0, 0, 0, 0, 42];
+	assert_eq!(*Seconds::<Duration>::from_sql(&Type::INT8, &raw).unwrap(), Duration::from_secs(42));
+
+	// `interval '1 day 02:03:04.5'`: microseconds, days, months
+	let raw = [0, 0, 0, 1, 184, 38, 135, 32, 0, 0, 0, 1, 0, 0, 0, 0];
+	assert_eq!(
+		*Ms::<Duration>::from_sql(&Type::INTERVAL, &raw).unwrap(),
+		Duration::from_millis(93_784_500)
+	);
+
+	// `timestamptz '2024-01-01 12:00:00.123456+00'`: microseconds since
+	// 2000-01-01
+	#[cfg(feature = "time")]
+	{
+		let raw = [0, 2, 176, 223, 227, 214, 210, 64];
+		let ts = crate::timestamp::Timestamp::from_sql(&Type::TIMESTAMPTZ, &raw).unwrap();
+		assert_eq!(ts, "2024-01-01T12:00:00.123456Z".parse().unwrap());
+		let mut buf = BytesMut::new();
+		ts.to_sql_checked(&Type::TIMESTAMPTZ, &mut buf).unwrap();
+		assert_eq!(&buf[..], raw);
+	}
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_duration_interval_sql() {
+	use std::time::Duration;
+
+	use crate::duration::{Hours, Ms, Nanos, Seconds};
+
+	let x = Seconds::<Duration>::from_uint(90);
+	assert_eq!(roundtrip(&x, &Type::INTERVAL).unwrap(), x);
+	let mut buf = BytesMut::new();
+	x.to_sql_checked(&Type::INTERVAL, &mut buf).unwrap();
+	assert_eq!(&buf[..], [&90_000_000_i64.to_be_bytes()[..], &[0; 8]].concat());
+
+	assert_eq!(
+		roundtrip(&Ms::<Duration>::from_uint(1500), &Type::INTERVAL).unwrap().as_millis(),
+		1500
+	);
+	let nanos = Nanos::<Duration>::from_uint(3000);
+	assert_eq!(roundtrip(&nanos, &Type::INTERVAL).unwrap(), nanos);
+	assert!(roundtrip(&Nanos::<Duration>::from_uint(1), &Type::INTERVAL).is_err());
+
+	let interval = |micros: i64, days: i32, months: i32| {
+		[&micros.to_be_bytes()[..], &days.to_be_bytes(), &months.to_be_bytes()].concat()
+	};
+	// `interval '1 day 1 hour'`
+	let raw = interval(3_600_000_000, 1, 0);
+	assert_eq!(
+		*Hours::<Duration>::from_sql(&Type::INTERVAL, &raw).unwrap(),
+		Duration::from_secs(25 * 3600)
+	);
+	assert!(Hours::<Duration>::from_sql(&Type::INTERVAL, &interval(1, 0, 0)).is_err());
+	assert!(Seconds::<Duration>::from_sql(&Type::INTERVAL, &interval(0, 0, 1)).is_err());
+	assert!(Seconds::<Duration>::from_sql(&Type::INTERVAL, &interval(-1_000_000, 0, 0)).is_err());
+	assert!(Seconds::<Duration>::from_sql(&Type::INTERVAL, &[0; 15]).is_err());
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "time"))]
+fn test_signed_duration_sql() {
+	use crate::duration::Seconds;
+
+	let x = Seconds::<time::Duration>::from_int(-30);
+	assert_eq!(roundtrip(&x, &Type::INT8).unwrap(), x);
+	assert_eq!(roundtrip(&x, &Type::INTERVAL).unwrap(), x);
+}
+
+#[test]
+#[cfg(all(feature = "time", feature = "serde"))]
+fn test_timestamp_sql() {
+	use crate::timestamp::{Timestamp, TimestampSecs};
+
+	let ts: Timestamp = "2024-01-01T14:00:00.123456+02:00".parse().unwrap();
+	assert_eq!(roundtrip(&ts, &Type::TIMESTAMPTZ).unwrap(), ts);
+	assert!(roundtrip(&ts, &Type::TIMESTAMP).is_err());
+
+	let secs = TimestampSecs::from(ts);
+	assert_eq!(roundtrip(&secs, &Type::TIMESTAMPTZ).unwrap().to_string(), "2024-01-01T12:00:00Z");
+	// Decoding normalizes as well
+	let mut buf = BytesMut::new();
+	ts.to_sql_checked(&Type::TIMESTAMPTZ, &mut buf).unwrap();
+	assert_eq!(TimestampSecs::from_sql(&Type::TIMESTAMPTZ, &buf).unwrap(), secs);
+}
+
+#[test]
+#[cfg(feature = "base_url")]
+fn test_base_url_sql() {
+	use crate::BaseUrl;
+
+	let url: BaseUrl = "https://example.com/api".parse().unwrap();
+	assert_eq!(roundtrip(&url, &Type::TEXT).unwrap().as_str(), "https://example.com/api/");
+	assert_eq!(roundtrip(&url, &Type::VARCHAR).unwrap(), url);
+	assert!(BaseUrl::from_sql(&Type::TEXT, b"not a url").is_err());
+}
@@ -0,0 +1,450 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal [`Serializer`] flattening a struct or map into URL query pairs,
+//! used by [`crate::UrlBuilder::query`]
+
+use serde::{
+	ser::{self, Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple},
+	Serialize, Serializer,
+};
+use thiserror::Error;
+use url::Url;
+
+/// Error when serializing query parameters with [`crate::UrlBuilder::query`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Query encoding error: {0}")]
+pub struct QueryEncodeError(String);
+
+impl ser::Error for QueryEncodeError {
+	fn custom<T: std::fmt::Display>(msg: T) -> Self {
+		Self(msg.to_string())
+	}
+}
+
+/// Appends `query` to the query of `url`. Leaves `url` untouched on error.
+pub(crate) fn append_query<T: Serialize + ?Sized>(
+	url: &mut Url,
+	query: &T,
+) -> Result<(), QueryEncodeError> {
+	let mut pairs = Vec::new();
+	query.serialize(PairsSerializer { pairs: &mut pairs })?;
+	if !pairs.is_empty() {
+		url.query_pairs_mut().extend_pairs(pairs);
+	}
+	Ok(())
+}
+
+fn error(msg: &str) -> QueryEncodeError {
+	QueryEncodeError(msg.to_owned())
+}
+
+/// Implements `Serializer` methods by failing with the given message
+macro_rules! reject {
+	($msg:expr; $($method:ident($($ty:ty),*)),* $(,)?) => {
+		$(
+			fn $method(self, $(_: $ty),*) -> Result<Self::Ok, Self::Error> {
+				Err(error($msg))
+			}
+		)*
+	};
+}
+
+/// Top level serializer, accepts structs and maps
+struct PairsSerializer<'a> {
+	pairs: &'a mut Vec<(String, String)>,
+}
+
+const NOT_A_MAP: &str = "query parameters must be a struct or a map";
+
+impl<'a> Serializer for PairsSerializer<'a> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+	type SerializeSeq = Impossible<(), QueryEncodeError>;
+	type SerializeTuple = Impossible<(), QueryEncodeError>;
+	type SerializeTupleStruct = Impossible<(), QueryEncodeError>;
+	type SerializeTupleVariant = Impossible<(), QueryEncodeError>;
+	type SerializeMap = MapSerializer<'a>;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Impossible<(), QueryEncodeError>;
+
+	reject!(NOT_A_MAP;
+		serialize_bool(bool), serialize_i8(i8), serialize_i16(i16), serialize_i32(i32),
+		serialize_i64(i64), serialize_u8(u8), serialize_u16(u16), serialize_u32(u32),
+		serialize_u64(u64), serialize_f32(f32), serialize_f64(f64), serialize_char(char),
+		serialize_str(&str), serialize_bytes(&[u8]),
+		serialize_unit_variant(&'static str, u32, &'static str),
+	);
+
+	fn serialize_none(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), QueryEncodeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, _: &'static str) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(
+		self,
+		_: &'static str,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: &T,
+	) -> Result<(), QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleStruct, QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleVariant, QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QueryEncodeError> {
+		Ok(MapSerializer { pairs: self.pairs, key: None })
+	}
+
+	fn serialize_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStruct, QueryEncodeError> {
+		Ok(self)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStructVariant, QueryEncodeError> {
+		Err(error(NOT_A_MAP))
+	}
+}
+
+impl SerializeStruct for PairsSerializer<'_> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		value.serialize(ValueSerializer { key, pairs: self.pairs })
+	}
+
+	fn end(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+}
+
+struct MapSerializer<'a> {
+	pairs: &'a mut Vec<(String, String)>,
+	key: Option<String>,
+}
+
+impl SerializeMap for MapSerializer<'_> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), QueryEncodeError> {
+		let mut pairs = Vec::new();
+		key.serialize(ValueSerializer { key: "", pairs: &mut pairs })?;
+		let Ok([(_, key)]) = <[_; 1]>::try_from(pairs) else {
+			return Err(error("query parameter names must be scalars"));
+		};
+		self.key = Some(key);
+		Ok(())
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		let key = self.key.take().ok_or_else(|| error("query parameter value without name"))?;
+		value.serialize(ValueSerializer { key: &key, pairs: self.pairs })
+	}
+
+	fn end(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+}
+
+/// Serializer of a single parameter value. Pushes nothing for [`None`] and a
+/// pair per element for sequences.
+struct ValueSerializer<'a> {
+	key: &'a str,
+	pairs: &'a mut Vec<(String, String)>,
+}
+
+impl ValueSerializer<'_> {
+	fn push(self, value: impl ToString) -> Result<(), QueryEncodeError> {
+		self.pairs.push((self.key.to_owned(), value.to_string()));
+		Ok(())
+	}
+}
+
+/// Implements `Serializer` methods by pushing the [`ToString`] representation
+macro_rules! push {
+	($($method:ident($ty:ty)),* $(,)?) => {
+		$(
+			fn $method(self, value: $ty) -> Result<(), QueryEncodeError> {
+				self.push(value)
+			}
+		)*
+	};
+}
+
+const NOT_A_SCALAR: &str = "query parameter values must be scalars or sequences of scalars";
+
+impl Serializer for ValueSerializer<'_> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+	type SerializeSeq = Self;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Impossible<(), QueryEncodeError>;
+	type SerializeTupleVariant = Impossible<(), QueryEncodeError>;
+	type SerializeMap = Impossible<(), QueryEncodeError>;
+	type SerializeStruct = Impossible<(), QueryEncodeError>;
+	type SerializeStructVariant = Impossible<(), QueryEncodeError>;
+
+	push!(
+		serialize_bool(bool),
+		serialize_i8(i8),
+		serialize_i16(i16),
+		serialize_i32(i32),
+		serialize_i64(i64),
+		serialize_i128(i128),
+		serialize_u8(u8),
+		serialize_u16(u16),
+		serialize_u32(u32),
+		serialize_u64(u64),
+		serialize_u128(u128),
+		serialize_f32(f32),
+		serialize_f64(f64),
+		serialize_char(char),
+		serialize_str(&str),
+	);
+
+	reject!(NOT_A_SCALAR; serialize_bytes(&[u8]));
+
+	fn serialize_none(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), QueryEncodeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, _: &'static str) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		variant: &'static str,
+	) -> Result<(), QueryEncodeError> {
+		self.push(variant)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(
+		self,
+		_: &'static str,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: &T,
+	) -> Result<(), QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self, QueryEncodeError> {
+		Ok(self)
+	}
+
+	fn serialize_tuple(self, _: usize) -> Result<Self, QueryEncodeError> {
+		Ok(self)
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleStruct, QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeTupleVariant, QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+
+	fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+
+	fn serialize_struct(
+		self,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStruct, QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_: &'static str,
+		_: u32,
+		_: &'static str,
+		_: usize,
+	) -> Result<Self::SerializeStructVariant, QueryEncodeError> {
+		Err(error(NOT_A_SCALAR))
+	}
+}
+
+impl SerializeSeq for ValueSerializer<'_> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		value.serialize(ValueSerializer { key: self.key, pairs: self.pairs })
+	}
+
+	fn end(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+}
+
+impl SerializeTuple for ValueSerializer<'_> {
+	type Ok = ();
+	type Error = QueryEncodeError;
+
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), QueryEncodeError> {
+		SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<(), QueryEncodeError> {
+		Ok(())
+	}
+}
+
+#[test]
+fn test_append_query() {
+	use std::collections::BTreeMap;
+
+	#[derive(Serialize)]
+	#[serde(rename_all = "snake_case")]
+	enum Dir {
+		Forward,
+	}
+
+	#[derive(Serialize)]
+	struct Query<'a> {
+		from: &'a str,
+		limit: u32,
+		dir: Dir,
+		filter: Option<&'a str>,
+		types: Vec<&'a str>,
+		pair: (bool, f64),
+	}
+
+	let mut url = Url::parse("http://example.com/messages?access=1").unwrap();
+	append_query(
+		&mut url,
+		&Query {
+			from: "t1&2 3",
+			limit: 10,
+			dir: Dir::Forward,
+			filter: None,
+			types: vec!["m.room.message", "m.reaction"],
+			pair: (true, 0.5),
+		},
+	)
+	.unwrap();
+	assert_eq!(
+		url.as_str(),
+		"http://example.com/messages?access=1&from=t1%262+3&limit=10&dir=forward&types=m.room.message&types=m.reaction&pair=true&pair=0.5"
+	);
+
+	let mut url = Url::parse("http://example.com/").unwrap();
+	append_query(&mut url, &BTreeMap::from([(1, "a"), (2, "b")])).unwrap();
+	assert_eq!(url.as_str(), "http://example.com/?1=a&2=b");
+
+	let mut url = Url::parse("http://example.com/").unwrap();
+	append_query(&mut url, &None::<Query<'_>>).unwrap();
+	append_query(&mut url, &BTreeMap::<String, String>::new()).unwrap();
+	assert_eq!(url.as_str(), "http://example.com/");
+
+	for result in [
+		append_query(&mut url, "a=b"),
+		append_query(&mut url, &vec![("a", "b")]),
+		append_query(&mut url, &BTreeMap::from([("a", BTreeMap::from([("b", "c")]))])),
+		append_query(&mut url, &BTreeMap::from([(vec![1, 2], "a")])),
+	] {
+		assert!(result.is_err());
+	}
+	assert_eq!(url.as_str(), "http://example.com/");
+}
@@ -3,7 +3,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Helpers for [`reqwest`]
-use std::{fmt, future::Future};
+#[cfg(feature = "base_url")]
+use std::path::PathBuf;
+use std::{
+	fmt,
+	future::Future,
+	time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "base_url")]
+use url::Url;
+
+use crate::duration::Ms;
+#[cfg(feature = "base_url")]
+use crate::duration::Seconds;
+#[cfg(feature = "base_url")]
+use crate::{base_url::UNIX_SCHEMES, BaseUrl};
 
 /// An alternative to [`reqwest::Response::error_for_status`] that also returns
 /// response body if it is present.
@@ -17,47 +33,1139 @@ use std::{fmt, future::Future};
 /// # Ok(())
 /// # }
 /// ```
+///
+/// With [`ErrorForStatusWithBody::error_for_status_with_json`] the body is
+/// also decoded into a structured error type:
+/// ```no_run
+/// # use famedly_rust_utils::reqwest::*;
+/// #[derive(Debug, serde::Deserialize)]
+/// struct MatrixError {
+/// 	errcode: String,
+/// 	error: Option<String>,
+/// }
+///
+/// # async fn mk_req() -> Result<(), ReqwestErrorWithBody> {
+/// let result = reqwest::get("http://invalid.example")
+/// 	.await?
+/// 	.error_for_status_with_json::<MatrixError>()
+/// 	.await;
+/// if let Err(ReqwestErrorWithJson { json: Some(error), .. }) = &result {
+/// 	if error.errcode == "M_LIMIT_EXCEEDED" {
+/// 		// back off
+/// 	}
+/// }
+/// result?;
+/// # Ok(())
+/// # }
+/// ```
 pub trait ErrorForStatusWithBody {
 	// Using explicit `impl Future` syntax here instead of `async_fn_in_trait` to
 	// make it `Send`
-	#[allow(missing_docs)]
+	/// For [`reqwest::Response`] reads at most [`DEFAULT_ERROR_BODY_LIMIT`]
+	/// bytes of the body, see
+	/// [`ErrorForStatusWithBody::error_for_status_with_body_limit`]
 	fn error_for_status_with_body(
 		self,
 	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send;
+
+	/// Cuts the body off after `limit` bytes and sets
+	/// [`ReqwestErrorWithBody::body_truncated`] if there was more.
+	/// [`reqwest::Response`] stops reading at the limit and doesn't read bodies
+	/// with a non-text `Content-Type`, which are replaced by a description like
+	/// `<1234 bytes of image/png>`.
+	fn error_for_status_with_body_limit(
+		self,
+		limit: usize,
+	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send
+	where
+		Self: Sized,
+	{
+		let response = self.error_for_status_with_body();
+		async move {
+			response.await.map_err(|mut error| {
+				if let Some(body) = error.body.as_mut().filter(|body| body.len() > limit) {
+					let mut end = limit;
+					while !body.is_char_boundary(end) {
+						end -= 1;
+					}
+					body.truncate(end);
+					error.body_truncated = true;
+				}
+				error
+			})
+		}
+	}
+
+	/// Same as [`ErrorForStatusWithBody::error_for_status_with_body`], also
+	/// decoding the body as JSON
+	fn error_for_status_with_json<E: DeserializeOwned>(
+		self,
+	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithJson<E>>> + Send
+	where
+		Self: Sized,
+	{
+		let response = self.error_for_status_with_body();
+		async { response.await.map_err(ReqwestErrorWithBody::into_json) }
+	}
 }
 
 /// Wrapper around [`reqwest::Error`] with optional response body
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub struct ReqwestErrorWithBody {
 	/// Error from [`reqwest`]
 	pub error: reqwest::Error,
 	/// Optional response body
 	pub body: Option<String>,
+	/// Whether `body` was cut off at the size limit
+	pub body_truncated: bool,
+	/// Number of attempts made, `1` unless the request was retried with
+	/// [`SendWithRetry::send_with_retry`]
+	pub attempts: u32,
 }
 
+/// Default body size limit of
+/// [`ErrorForStatusWithBody::error_for_status_with_body`]
+pub const DEFAULT_ERROR_BODY_LIMIT: usize = 64 * 1024;
+
 impl From<reqwest::Error> for ReqwestErrorWithBody {
 	fn from(error: reqwest::Error) -> ReqwestErrorWithBody {
-		Self { error, body: None }
+		Self { error, body: None, body_truncated: false, attempts: 1 }
 	}
 }
 
 impl fmt::Display for ReqwestErrorWithBody {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{} with body {}", self.error, self.body.as_deref().unwrap_or("<no body>"))
+		write!(f, "{} with body {}", self.error, self.body.as_deref().unwrap_or("<no body>"))?;
+		if self.body_truncated {
+			write!(f, " [truncated]")?;
+		}
+		if self.attempts > 1 {
+			write!(f, " after {} attempts", self.attempts)?;
+		}
+		Ok(())
+	}
+}
+
+impl ReqwestErrorWithBody {
+	/// Status code of the response, if the error came from one
+	#[must_use]
+	pub fn status(&self) -> Option<reqwest::StatusCode> {
+		self.error.status()
+	}
+
+	/// Body decoded as JSON, `None` if there is no body or it is not a valid
+	/// `E`
+	#[must_use]
+	pub fn json<E: DeserializeOwned>(&self) -> Option<E> {
+		serde_json::from_str(self.body.as_deref()?).ok()
+	}
+
+	/// Decodes the body as JSON, keeping the raw body
+	#[must_use]
+	pub fn into_json<E: DeserializeOwned>(self) -> ReqwestErrorWithJson<E> {
+		ReqwestErrorWithJson { json: self.json(), inner: self }
+	}
+}
+
+/// [`ReqwestErrorWithBody`] with the body decoded as JSON, e.g. into a
+/// Matrix-style `{"errcode": ..., "error": ...}` type
+#[derive(Debug, thiserror::Error)]
+pub struct ReqwestErrorWithJson<E> {
+	/// Error with the raw body
+	pub inner: ReqwestErrorWithBody,
+	/// Decoded body, `None` if there is no body or it is not a valid `E`
+	pub json: Option<E>,
+}
+
+impl<E> ReqwestErrorWithJson<E> {
+	/// Status code of the response, if the error came from one
+	#[must_use]
+	pub fn status(&self) -> Option<reqwest::StatusCode> {
+		self.inner.status()
+	}
+}
+
+impl<E> From<ReqwestErrorWithJson<E>> for ReqwestErrorWithBody {
+	fn from(error: ReqwestErrorWithJson<E>) -> ReqwestErrorWithBody {
+		error.inner
+	}
+}
+
+impl<E> From<reqwest::Error> for ReqwestErrorWithJson<E> {
+	fn from(error: reqwest::Error) -> ReqwestErrorWithJson<E> {
+		Self { inner: error.into(), json: None }
+	}
+}
+
+impl<E> fmt::Display for ReqwestErrorWithJson<E> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
 	}
 }
 
 impl ErrorForStatusWithBody for reqwest::Response {
-	#[allow(clippy::manual_async_fn)]
 	fn error_for_status_with_body(
 		self,
 	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send {
-		async {
+		self.error_for_status_with_body_limit(DEFAULT_ERROR_BODY_LIMIT)
+	}
+
+	#[allow(clippy::manual_async_fn)]
+	fn error_for_status_with_body_limit(
+		self,
+		limit: usize,
+	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send {
+		async move {
 			if let Err(error) = self.error_for_status_ref() {
-				Err(ReqwestErrorWithBody { error, body: self.text().await.ok() })
+				let (body, body_truncated) = read_error_body(self, limit).await;
+				Err(ReqwestErrorWithBody { error, body, body_truncated, attempts: 1 })
 			} else {
 				Ok(self)
 			}
 		}
 	}
 }
+
+/// Reads at most `limit` bytes of a textual body, returning the body and
+/// whether it was truncated. A body cut off by a read error counts as
+/// truncated.
+async fn read_error_body(mut response: reqwest::Response, limit: usize) -> (Option<String>, bool) {
+	let content_type = response
+		.headers()
+		.get(reqwest::header::CONTENT_TYPE)
+		.and_then(|content_type| content_type.to_str().ok())
+		.filter(|content_type| !is_textual(content_type));
+	if let Some(content_type) = content_type {
+		let body = match response.content_length() {
+			Some(length) => format!("<{length} bytes of {content_type}>"),
+			None => format!("<binary body of type {content_type}>"),
+		};
+		return (Some(body), false);
+	}
+
+	let mut body = Vec::new();
+	let mut truncated = false;
+	loop {
+		match response.chunk().await {
+			Ok(Some(chunk)) => {
+				let remaining = limit - body.len();
+				if chunk.len() > remaining {
+					body.extend_from_slice(&chunk[..remaining]);
+					truncated = true;
+					break;
+				}
+				body.extend_from_slice(&chunk);
+			}
+			Ok(None) => break,
+			Err(_) if body.is_empty() => return (None, false),
+			Err(_) => {
+				truncated = true;
+				break;
+			}
+		}
+	}
+	if truncated {
+		// Drop a character cut in half by the limit
+		if let Err(error) = std::str::from_utf8(&body) {
+			if error.error_len().is_none() {
+				body.truncate(error.valid_up_to());
+			}
+		}
+	}
+	(Some(String::from_utf8_lossy(&body).into_owned()), truncated)
+}
+
+/// Whether a `Content-Type` denotes text that is useful in error messages
+fn is_textual(content_type: &str) -> bool {
+	let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+	mime.starts_with("text/")
+		|| mime.ends_with("+json")
+		|| mime.ends_with("+xml")
+		|| matches!(
+			mime.as_str(),
+			"application/json"
+				| "application/xml"
+				| "application/javascript"
+				| "application/x-www-form-urlencoded"
+		)
+}
+
+/// [`reqwest::Client`] bound to a [`BaseUrl`]. For Unix socket base URLs (see
+/// [`BaseUrl::unix_socket`]) the client dials the socket and request URLs are
+/// rewritten to `http://localhost/...` or `https://localhost/...`. Unix sockets
+/// are only supported on Unix platforms.
+/// ```no_run
+/// # use famedly_rust_utils::{BaseUrl, reqwest::{BaseUrlClient, BaseUrlClientError}};
+/// # async fn health() -> Result<(), BaseUrlClientError> {
+/// let base_url: BaseUrl = "unix:///run/svc.sock".parse().unwrap();
+/// let client = BaseUrlClient::new(base_url)?;
+/// let status = client.get("health").send().await?.status();
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "base_url")]
+#[derive(Debug, Clone)]
+pub struct BaseUrlClient {
+	client: reqwest::Client,
+	base_url: BaseUrl,
+}
+
+#[cfg(feature = "base_url")]
+impl BaseUrlClient {
+	/// Client with default settings
+	pub fn new(base_url: BaseUrl) -> Result<Self, BaseUrlClientError> {
+		Self::with_builder(base_url, reqwest::Client::builder())
+	}
+
+	/// Client built from `builder`. TCP and proxy settings are ignored for Unix
+	/// socket base URLs, which fail on non-Unix platforms.
+	pub fn with_builder(
+		base_url: BaseUrl,
+		builder: reqwest::ClientBuilder,
+	) -> Result<Self, BaseUrlClientError> {
+		let builder = match base_url.unix_socket() {
+			#[cfg(unix)]
+			Some(socket) => builder.unix_socket(socket),
+			#[cfg(not(unix))]
+			Some(_) => return Err(BaseUrlClientError::UnixSocketUnsupported),
+			None => builder,
+		};
+		Ok(Self { client: builder.build()?, base_url })
+	}
+
+	/// Rewrites `url` under a Unix socket base URL into the
+	/// `http://localhost/...` or `https://localhost/...` URL to request over
+	/// the socket. Other URLs are returned as is.
+	fn dial_url(url: &Url) -> Url {
+		let Some((_, scheme)) = UNIX_SCHEMES.iter().find(|(unix, _)| *unix == url.scheme()) else {
+			return url.clone();
+		};
+		let mut http = format!("{scheme}://localhost{}", url.path());
+		if let Some(query) = url.query() {
+			http.push('?');
+			http.push_str(query);
+		}
+		// Path and query are already valid, so this can't fail
+		Url::parse(&http).unwrap_or_else(|_| url.clone())
+	}
+
+	/// Base URL of the requests
+	#[must_use]
+	pub fn base_url(&self) -> &BaseUrl {
+		&self.base_url
+	}
+
+	/// Underlying client. Requests to Unix socket base URLs have to go through
+	/// [`BaseUrlClient::request`].
+	#[must_use]
+	pub fn client(&self) -> &reqwest::Client {
+		&self.client
+	}
+
+	/// Request to `url`, usually built with [`BaseUrl::build_url`]
+	pub fn request(&self, method: reqwest::Method, url: &Url) -> reqwest::RequestBuilder {
+		self.client.request(method, Self::dial_url(url))
+	}
+
+	/// `GET` request to `path` appended to the base URL as in
+	/// [`BaseUrl::with_path`]
+	pub fn get(&self, path: impl AsRef<str>) -> reqwest::RequestBuilder {
+		self.request(reqwest::Method::GET, &self.base_url.with_path(path))
+	}
+
+	/// `POST` request to `path` appended to the base URL as in
+	/// [`BaseUrl::with_path`]
+	pub fn post(&self, path: impl AsRef<str>) -> reqwest::RequestBuilder {
+		self.request(reqwest::Method::POST, &self.base_url.with_path(path))
+	}
+}
+
+/// Error building a [`BaseUrlClient`]
+#[cfg(feature = "base_url")]
+#[derive(Debug, thiserror::Error)]
+pub enum BaseUrlClientError {
+	/// Unix socket base URL on a platform without Unix sockets
+	#[error("Unix socket base URLs are not supported on this platform")]
+	UnixSocketUnsupported,
+	/// Invalid client settings
+	#[error("Failed to build http client: {0}")]
+	Reqwest(#[from] reqwest::Error),
+}
+
+/// Settings for a [`BaseUrlClient`], usually part of a service config.
+/// Timeouts are in seconds and default to the [`reqwest`] defaults.
+/// ```
+/// # use famedly_rust_utils::reqwest::HttpClientConfig;
+/// let config: HttpClientConfig = serde_json::from_value(serde_json::json!({
+/// 	"base_url": "https://api.example.com/v1",
+/// 	"connect_timeout": 5,
+/// 	"request_timeout": 30,
+/// 	"user_agent": "my-service/1.0",
+/// 	"proxy": {"url": "http://proxy.internal:3128", "no_proxy": ["localhost"]},
+/// 	"tls": {"min_version": "1.3"},
+/// }))
+/// .unwrap();
+/// let client = config.build().unwrap();
+/// assert_eq!(client.base_url().as_str(), "https://api.example.com/v1/");
+/// ```
+#[cfg(feature = "base_url")]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct HttpClientConfig {
+	/// Base URL of all requests
+	pub base_url: BaseUrl,
+	/// Timeout for establishing a connection
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub connect_timeout: Option<Seconds<Duration>>,
+	/// Timeout for a whole request, from connecting until the response body is
+	/// read
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub request_timeout: Option<Seconds<Duration>>,
+	/// How long idle connections are kept in the pool
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pool_idle_timeout: Option<Seconds<Duration>>,
+	/// Value of the `User-Agent` header
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub user_agent: Option<String>,
+	/// Proxy for all requests. Without it the system proxy settings are used.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub proxy: Option<ProxyConfig>,
+	/// TLS settings
+	#[serde(default)]
+	pub tls: TlsConfig,
+}
+
+/// Proxy settings of [`HttpClientConfig`]
+#[cfg(feature = "base_url")]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ProxyConfig {
+	/// Proxy URL, may contain credentials
+	pub url: Url,
+	/// Hosts, domains and IP ranges to connect to directly, in the format of
+	/// the `NO_PROXY` environment variable
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub no_proxy: Vec<String>,
+}
+
+/// TLS settings of [`HttpClientConfig`]
+#[cfg(feature = "base_url")]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+	/// PEM files with additional root certificates
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub root_certificates: Vec<PathBuf>,
+	/// Minimum TLS version
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub min_version: Option<TlsVersion>,
+	/// Disables certificate validation. Only use this for testing.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub danger_accept_invalid_certs: bool,
+}
+
+/// TLS protocol version
+#[cfg(feature = "base_url")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TlsVersion {
+	/// TLS 1.2
+	#[serde(rename = "1.2")]
+	Tls12,
+	/// TLS 1.3
+	#[serde(rename = "1.3")]
+	Tls13,
+}
+
+/// Error building a client from [`HttpClientConfig`]
+#[cfg(feature = "base_url")]
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientConfigError {
+	/// Root certificate file could not be read
+	#[error("Failed to read root certificate {}: {source}", path.display())]
+	ReadCertificate {
+		/// Path of the certificate file
+		path: PathBuf,
+		/// Error reading it
+		source: std::io::Error,
+	},
+	/// Invalid setting, e.g. a malformed certificate or user agent
+	#[error("Invalid http client config: {0}")]
+	Reqwest(#[from] reqwest::Error),
+	/// Unix socket base URL on a platform without Unix sockets
+	#[error("Unix socket base URLs are not supported on this platform")]
+	UnixSocketUnsupported,
+}
+
+#[cfg(feature = "base_url")]
+impl From<BaseUrlClientError> for HttpClientConfigError {
+	fn from(error: BaseUrlClientError) -> Self {
+		match error {
+			BaseUrlClientError::UnixSocketUnsupported => Self::UnixSocketUnsupported,
+			BaseUrlClientError::Reqwest(error) => Self::Reqwest(error),
+		}
+	}
+}
+
+#[cfg(feature = "base_url")]
+impl HttpClientConfig {
+	/// Config with default settings for `base_url`
+	#[must_use]
+	pub fn new(base_url: BaseUrl) -> Self {
+		Self {
+			base_url,
+			connect_timeout: None,
+			request_timeout: None,
+			pool_idle_timeout: None,
+			user_agent: None,
+			proxy: None,
+			tls: TlsConfig::default(),
+		}
+	}
+
+	/// Builder with all settings applied, to customize it further before
+	/// passing it to [`BaseUrlClient::with_builder`]
+	pub fn client_builder(&self) -> Result<reqwest::ClientBuilder, HttpClientConfigError> {
+		let mut builder = reqwest::Client::builder();
+		if let Some(timeout) = self.connect_timeout {
+			builder = builder.connect_timeout(*timeout);
+		}
+		if let Some(timeout) = self.request_timeout {
+			builder = builder.timeout(*timeout);
+		}
+		if let Some(timeout) = self.pool_idle_timeout {
+			builder = builder.pool_idle_timeout(*timeout);
+		}
+		if let Some(user_agent) = &self.user_agent {
+			builder = builder.user_agent(user_agent);
+		}
+		if let Some(proxy) = &self.proxy {
+			let no_proxy = reqwest::NoProxy::from_string(&proxy.no_proxy.join(","));
+			builder = builder.proxy(reqwest::Proxy::all(proxy.url.as_str())?.no_proxy(no_proxy));
+		}
+		for path in &self.tls.root_certificates {
+			let pem = std::fs::read(path).map_err(|source| {
+				HttpClientConfigError::ReadCertificate { path: path.clone(), source }
+			})?;
+			for certificate in reqwest::Certificate::from_pem_bundle(&pem)? {
+				builder = builder.add_root_certificate(certificate);
+			}
+		}
+		if let Some(version) = self.tls.min_version {
+			builder = builder.tls_version_min(match version {
+				TlsVersion::Tls12 => reqwest::tls::Version::TLS_1_2,
+				TlsVersion::Tls13 => reqwest::tls::Version::TLS_1_3,
+			});
+		}
+		Ok(builder.tls_danger_accept_invalid_certs(self.tls.danger_accept_invalid_certs))
+	}
+
+	/// Client with all settings applied
+	pub fn build(&self) -> Result<BaseUrlClient, HttpClientConfigError> {
+		Ok(BaseUrlClient::with_builder(self.base_url.clone(), self.client_builder()?)?)
+	}
+}
+
+#[cfg(feature = "base_url")]
+impl From<BaseUrl> for HttpClientConfig {
+	fn from(base_url: BaseUrl) -> Self {
+		Self::new(base_url)
+	}
+}
+
+#[cfg(feature = "base_url")]
+impl TryFrom<&HttpClientConfig> for BaseUrlClient {
+	type Error = HttpClientConfigError;
+	fn try_from(config: &HttpClientConfig) -> Result<Self, Self::Error> {
+		config.build()
+	}
+}
+
+/// Retry policy for [`SendWithRetry::send_with_retry`]. Connection errors,
+/// timeouts and `408`, `429`, `502`, `503` and `504` responses are retried with
+/// exponential backoff, or after the delay from the `Retry-After` header if
+/// present. Durations are in milliseconds.
+/// ```
+/// # use famedly_rust_utils::reqwest::RetryConfig;
+/// let config: RetryConfig = serde_json::from_value(serde_json::json!({
+/// 	"max_attempts": 5,
+/// 	"max_elapsed_time": 10000,
+/// }))
+/// .unwrap();
+/// assert_eq!(config.initial_backoff, RetryConfig::default().initial_backoff);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+	/// Maximum number of attempts including the first one
+	pub max_attempts: u32,
+	/// Delay before the first retry, doubled for every further retry
+	pub initial_backoff: Ms<Duration>,
+	/// Upper bound of the backoff delay, also applied to `Retry-After` delays
+	pub max_backoff: Ms<Duration>,
+	/// No retry is made if it would start later than this after the first
+	/// attempt
+	pub max_elapsed_time: Option<Ms<Duration>>,
+	/// Randomize each backoff delay to between half and all of it
+	pub jitter: bool,
+	/// Also retry methods that are not idempotent, like `POST` and `PATCH`
+	pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryConfig {
+	fn default() -> Self {
+		Self {
+			max_attempts: 3,
+			initial_backoff: Ms(Duration::from_millis(100)),
+			max_backoff: Ms(Duration::from_secs(10)),
+			max_elapsed_time: None,
+			jitter: true,
+			retry_non_idempotent: false,
+		}
+	}
+}
+
+impl RetryConfig {
+	/// Backoff delay before retry number `retry`, starting at `1`
+	fn backoff(&self, retry: u32) -> Duration {
+		let backoff = self
+			.initial_backoff
+			.saturating_mul(2_u32.saturating_pow(retry.saturating_sub(1)))
+			.min(*self.max_backoff);
+		if !self.jitter {
+			return backoff;
+		}
+		let half = backoff / 2;
+		let nanos = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+		half + Duration::from_nanos(crate::random_u64() % nanos.saturating_add(1))
+	}
+
+	/// Whether a retry after `delay` would start within
+	/// [`RetryConfig::max_elapsed_time`]
+	fn within_max_elapsed_time(&self, start: Instant, delay: Duration) -> bool {
+		match self.max_elapsed_time {
+			Some(max) => start.elapsed().checked_add(delay).is_some_and(|elapsed| elapsed <= *max),
+			None => true,
+		}
+	}
+}
+
+/// Statuses of responses that are retried
+const RETRY_STATUSES: [reqwest::StatusCode; 5] = [
+	reqwest::StatusCode::REQUEST_TIMEOUT,
+	reqwest::StatusCode::TOO_MANY_REQUESTS,
+	reqwest::StatusCode::BAD_GATEWAY,
+	reqwest::StatusCode::SERVICE_UNAVAILABLE,
+	reqwest::StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// Delay requested by the `Retry-After` header, either in seconds or, with the
+/// `time` feature, as an HTTP date
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+	let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+	if let Ok(seconds) = value.parse() {
+		return Some(Duration::from_secs(seconds));
+	}
+	#[cfg(feature = "time")]
+	if let Ok(date) = value.parse::<crate::timestamp::HttpDate>() {
+		let delay = crate::timestamp::Timestamp::from(date) - crate::timestamp::Timestamp::now();
+		return Some(delay.try_into().unwrap_or_default());
+	}
+	None
+}
+
+/// Sends a request, retrying transient failures according to a
+/// [`RetryConfig`]. Requests with streaming bodies can't be cloned and are sent
+/// only once. Non-success responses are turned into errors like with
+/// [`ErrorForStatusWithBody::error_for_status_with_body`]. Requires a tokio
+/// runtime with the time driver enabled.
+/// ```no_run
+/// # use famedly_rust_utils::reqwest::*;
+/// # async fn mk_req() -> Result<(), ReqwestErrorWithBody> {
+/// let response = reqwest::Client::new()
+/// 	.get("http://invalid.example")
+/// 	.send_with_retry(&RetryConfig::default())
+/// 	.await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait SendWithRetry {
+	#[allow(missing_docs)]
+	fn send_with_retry(
+		self,
+		config: &RetryConfig,
+	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send;
+}
+
+impl SendWithRetry for reqwest::RequestBuilder {
+	#[allow(clippy::manual_async_fn)]
+	fn send_with_retry(
+		self,
+		config: &RetryConfig,
+	) -> impl Future<Output = Result<reqwest::Response, ReqwestErrorWithBody>> + Send {
+		async move {
+			let start = Instant::now();
+			let (client, request) = self.build_split();
+			let mut request = request?;
+			let max_attempts = if request.method().is_idempotent() || config.retry_non_idempotent {
+				config.max_attempts
+			} else {
+				1
+			};
+			let mut attempts = 1;
+			loop {
+				let retry = if attempts < max_attempts { request.try_clone() } else { None };
+				let result = client.execute(request).await;
+				let delay = match &result {
+					Ok(response) if RETRY_STATUSES.contains(&response.status()) => {
+						Some(retry_after(response).map_or_else(
+							|| config.backoff(attempts),
+							|delay| delay.min(*config.max_backoff),
+						))
+					}
+					Err(error)
+						if error.is_connect() || error.is_timeout() || error.is_request() =>
+					{
+						Some(config.backoff(attempts))
+					}
+					_ => None,
+				};
+				match (retry, delay) {
+					(Some(retry), Some(delay)) if config.within_max_elapsed_time(start, delay) => {
+						tokio::time::sleep(delay).await;
+						request = retry;
+						attempts += 1;
+					}
+					_ => {
+						return match result {
+							Ok(response) => response.error_for_status_with_body().await,
+							Err(error) => Err(error.into()),
+						}
+						.map_err(|error| ReqwestErrorWithBody { attempts, ..error });
+					}
+				}
+			}
+		}
+	}
+}
+
+#[test]
+#[cfg(all(unix, feature = "base_url"))]
+fn test_unix_socket_client() {
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	#[derive(serde::Serialize)]
+	struct Query {
+		full: u8,
+	}
+
+	let dir = std::env::temp_dir().join(format!("famedly-rust-utils-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let socket = dir.join("svc.sock");
+	let _ = std::fs::remove_file(&socket);
+
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	runtime.block_on(async {
+		let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+		// Responds with the request line and `Host` header
+		let server = tokio::spawn(async move {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut request = Vec::new();
+			while !request.ends_with(b"\r\n\r\n") {
+				let mut buf = [0; 1024];
+				let n = stream.read(&mut buf).await.unwrap();
+				assert_ne!(n, 0);
+				request.extend_from_slice(&buf[..n]);
+			}
+			let request = String::from_utf8(request).unwrap();
+			let host = request.lines().find(|line| line.starts_with("host:")).unwrap();
+			let body = format!("{}\n{host}", request.lines().next().unwrap());
+			let response = format!(
+				"HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+				body.len()
+			);
+			stream.write_all(response.as_bytes()).await.unwrap();
+		});
+
+		let base_url: BaseUrl = format!("unix://{}", socket.display()).parse().unwrap();
+		let base_url: BaseUrl = base_url.with_path("api").try_into().unwrap();
+		let client = BaseUrlClient::new(base_url).unwrap();
+		let url =
+			client.base_url().build_url().path("v1/health").query(&Query { full: 1 }).unwrap();
+		let response =
+			client.request(reqwest::Method::GET, &url.build().unwrap()).send().await.unwrap();
+		assert_eq!(
+			response.text().await.unwrap(),
+			"GET /api/v1/health?full=1 HTTP/1.1\nhost: localhost"
+		);
+		server.await.unwrap();
+	});
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "base_url")]
+fn test_dial_url() {
+	let base_url: BaseUrl = "https+unix://%2Frun%2Fsvc.sock/api".parse().unwrap();
+	let url = base_url.build_url().path("a b").build().unwrap();
+	assert_eq!(BaseUrlClient::dial_url(&url).as_str(), "https://localhost/api/a%20b");
+	let url = Url::parse("http+unix://%2Frun%2Fsvc.sock/x?q=1#f").unwrap();
+	assert_eq!(BaseUrlClient::dial_url(&url).as_str(), "http://localhost/x?q=1");
+
+	let url = Url::parse("http://example.com/api?q=1").unwrap();
+	assert_eq!(BaseUrlClient::dial_url(&url), url);
+
+	let base_url: BaseUrl = "unix:///run/svc.sock".parse().unwrap();
+	#[cfg(unix)]
+	assert!(BaseUrlClient::new(base_url).is_ok());
+	#[cfg(not(unix))]
+	assert!(matches!(BaseUrlClient::new(base_url), Err(BaseUrlClientError::UnixSocketUnsupported)));
+}
+
+#[test]
+#[cfg(feature = "base_url")]
+fn test_http_client_config() {
+	let config: HttpClientConfig = serde_json::from_value(serde_json::json!({
+		"base_url": "https://api.example.com",
+		"request_timeout": 30,
+		"pool_idle_timeout": 60,
+		"tls": {"min_version": "1.2", "danger_accept_invalid_certs": true},
+	}))
+	.unwrap();
+	assert_eq!(config.request_timeout, Some(Seconds(Duration::from_secs(30))));
+	assert_eq!(config.connect_timeout, None);
+	assert_eq!(config.tls.min_version, Some(TlsVersion::Tls12));
+	assert_eq!(
+		serde_json::from_value::<HttpClientConfig>(serde_json::to_value(&config).unwrap()).unwrap(),
+		config
+	);
+	assert_eq!(
+		serde_json::to_value(HttpClientConfig::new(config.base_url.clone())).unwrap(),
+		serde_json::json!({"base_url": "https://api.example.com/", "tls": {}})
+	);
+	assert_eq!(config.build().unwrap().base_url(), &config.base_url);
+
+	for invalid in [
+		serde_json::json!({}),
+		serde_json::json!({"base_url": "https://api.example.com", "timeout": 30}),
+		serde_json::json!({"base_url": "https://api.example.com", "tls": {"min_version": "1.1"}}),
+		serde_json::json!({"base_url": "https://api.example.com", "proxy": {"url": "not a url"}}),
+	] {
+		assert!(serde_json::from_value::<HttpClientConfig>(invalid.clone()).is_err(), "{invalid}");
+	}
+
+	let mut config = HttpClientConfig::from(config.base_url);
+	config.user_agent = Some("invalid\nuser agent".to_owned());
+	assert!(matches!(config.build(), Err(HttpClientConfigError::Reqwest(_))));
+	config.user_agent = None;
+	config.tls.root_certificates = vec!["/nonexistent/ca.pem".into()];
+	assert!(matches!(
+		config.build(),
+		Err(HttpClientConfigError::ReadCertificate { path, .. }) if path.as_os_str() == "/nonexistent/ca.pem"
+	));
+}
+
+/// Serves `responses` in order, one per connection. Returns the URL and the
+/// number of requests received so far.
+#[cfg(test)]
+async fn serve_responses(
+	responses: Vec<String>,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+	use std::sync::{atomic::Ordering, Arc};
+
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}/", listener.local_addr().unwrap());
+	let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+	let counter = requests.clone();
+	tokio::spawn(async move {
+		for response in responses {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut request = Vec::new();
+			while !request.ends_with(b"\r\n\r\n") {
+				let mut buf = [0; 1024];
+				let n = stream.read(&mut buf).await.unwrap();
+				assert_ne!(n, 0);
+				request.extend_from_slice(&buf[..n]);
+			}
+			counter.fetch_add(1, Ordering::SeqCst);
+			stream.write_all(response.as_bytes()).await.unwrap();
+		}
+	});
+	(url, requests)
+}
+
+#[cfg(test)]
+fn http_response(status: &str, headers: &str, body: &str) -> String {
+	format!(
+		"HTTP/1.1 {status}\r\n{headers}content-length: {}\r\nconnection: close\r\n\r\n{body}",
+		body.len()
+	)
+}
+
+#[test]
+fn test_send_with_retry() {
+	use std::sync::atomic::Ordering;
+
+	let unavailable = || http_response("503 Service Unavailable", "", "busy");
+	let ok = || http_response("200 OK", "", "ok");
+	let fast = RetryConfig { initial_backoff: Ms(Duration::from_millis(1)), ..Default::default() };
+	let slow = RetryConfig {
+		initial_backoff: Ms(Duration::from_secs(3600)),
+		max_backoff: Ms(Duration::from_secs(3600)),
+		..fast.clone()
+	};
+	let client = reqwest::Client::new();
+
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	runtime.block_on(async {
+		let (url, requests) = serve_responses(vec![unavailable(), unavailable(), ok()]).await;
+		let response = client.get(&url).send_with_retry(&fast).await.unwrap();
+		assert_eq!(response.text().await.unwrap(), "ok");
+		assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+		let (url, requests) = serve_responses(vec![unavailable(); 3]).await;
+		let error = client.get(&url).send_with_retry(&fast).await.unwrap_err();
+		assert_eq!(error.error.status(), Some(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+		assert_eq!((error.body.as_deref(), error.attempts), (Some("busy"), 3));
+		assert!(error.to_string().ends_with("with body busy after 3 attempts"), "{error}");
+		assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+		// Client errors are not retried
+		let (url, requests) = serve_responses(vec![http_response("404 Not Found", "", "")]).await;
+		let error = client.get(&url).send_with_retry(&fast).await.unwrap_err();
+		assert_eq!(error.attempts, 1);
+		assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+		// Non-idempotent methods are only retried if enabled
+		let (url, requests) = serve_responses(vec![unavailable(), ok()]).await;
+		let error = client.post(&url).send_with_retry(&fast).await.unwrap_err();
+		assert_eq!(error.attempts, 1);
+		assert_eq!(requests.load(Ordering::SeqCst), 1);
+		let retry_post = RetryConfig { retry_non_idempotent: true, ..fast.clone() };
+		let (url, _) = serve_responses(vec![unavailable(), ok()]).await;
+		client.post(&url).send_with_retry(&retry_post).await.unwrap();
+
+		// `Retry-After` overrides the backoff
+		#[cfg(feature = "time")]
+		let retry_after = "Sun, 06 Nov 1994 08:49:37 GMT";
+		#[cfg(not(feature = "time"))]
+		let retry_after = "0";
+		let responses = vec![
+			http_response(
+				"503 Service Unavailable",
+				&format!("retry-after: {retry_after}\r\n"),
+				"",
+			),
+			http_response("429 Too Many Requests", "retry-after: 0\r\n", ""),
+			ok(),
+		];
+		let (url, _) = serve_responses(responses).await;
+		let config = RetryConfig { max_attempts: 3, ..slow.clone() };
+		client.get(&url).send_with_retry(&config).await.unwrap();
+
+		// `Retry-After` is capped at `max_backoff`
+		let responses = vec![
+			http_response("503 Service Unavailable", &format!("retry-after: {}\r\n", u64::MAX), ""),
+			ok(),
+		];
+		let (url, requests) = serve_responses(responses).await;
+		let config = RetryConfig { max_backoff: Ms(Duration::from_millis(10)), ..fast.clone() };
+		client.get(&url).send_with_retry(&config).await.unwrap();
+		assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+		// No retry beyond `max_elapsed_time`
+		let (url, requests) = serve_responses(vec![unavailable(), ok()]).await;
+		let config =
+			RetryConfig { max_elapsed_time: Some(Ms(Duration::from_secs(60))), ..slow.clone() };
+		let error = client.get(&url).send_with_retry(&config).await.unwrap_err();
+		assert_eq!(error.attempts, 1);
+		assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}/", listener.local_addr().unwrap());
+		drop(listener);
+		let error = client.get(&url).send_with_retry(&fast).await.unwrap_err();
+		assert!(error.error.is_connect());
+		assert_eq!((error.body, error.attempts), (None, 3));
+	});
+}
+
+#[test]
+fn test_retry_backoff() {
+	let config = RetryConfig {
+		initial_backoff: Ms(Duration::from_millis(100)),
+		max_backoff: Ms(Duration::from_millis(300)),
+		jitter: false,
+		..Default::default()
+	};
+	let backoffs: Vec<_> = (1..=4).map(|retry| config.backoff(retry).as_millis()).collect();
+	assert_eq!(backoffs, [100, 200, 300, 300]);
+	assert_eq!(config.backoff(u32::MAX), Duration::from_millis(300));
+
+	let limited =
+		RetryConfig { max_elapsed_time: Some(Ms(Duration::from_secs(1))), ..config.clone() };
+	assert!(limited.within_max_elapsed_time(Instant::now(), Duration::from_millis(10)));
+	assert!(!limited.within_max_elapsed_time(Instant::now(), Duration::from_secs(2)));
+	assert!(!limited.within_max_elapsed_time(Instant::now(), Duration::MAX));
+	assert!(config.within_max_elapsed_time(Instant::now(), Duration::MAX));
+
+	let jittered = RetryConfig { jitter: true, ..config };
+	for _ in 0..100 {
+		let backoff = jittered.backoff(2);
+		assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(200));
+	}
+
+	assert_eq!(
+		serde_json::to_value(RetryConfig::default()).unwrap(),
+		serde_json::json!({
+			"max_attempts": 3,
+			"initial_backoff": 100,
+			"max_backoff": 10000,
+			"max_elapsed_time": null,
+			"jitter": true,
+			"retry_non_idempotent": false,
+		})
+	);
+	assert!(serde_json::from_value::<RetryConfig>(serde_json::json!({"attempts": 3})).is_err());
+}
+
+#[test]
+fn test_error_for_status_with_json() {
+	#[derive(Debug, PartialEq, serde::Deserialize)]
+	struct MatrixError {
+		errcode: String,
+		error: Option<String>,
+	}
+
+	let client = reqwest::Client::new();
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	runtime.block_on(async {
+		let body = r#"{"errcode": "M_FORBIDDEN", "error": "Forbidden"}"#;
+		let (url, _) = serve_responses(vec![
+			http_response("403 Forbidden", "content-type: application/json\r\n", body),
+			http_response("502 Bad Gateway", "", "<html>Bad Gateway</html>"),
+			http_response("200 OK", "", "ok"),
+		])
+		.await;
+
+		let error = client
+			.get(&url)
+			.send()
+			.await
+			.unwrap()
+			.error_for_status_with_json::<MatrixError>()
+			.await
+			.unwrap_err();
+		assert_eq!(error.status(), Some(reqwest::StatusCode::FORBIDDEN));
+		assert_eq!(
+			error.json,
+			Some(MatrixError {
+				errcode: "M_FORBIDDEN".to_owned(),
+				error: Some("Forbidden".to_owned())
+			})
+		);
+		assert_eq!(error.to_string(), error.inner.to_string());
+		let error = ReqwestErrorWithBody::from(error);
+		assert_eq!(error.body.as_deref(), Some(body));
+		assert_eq!(error.json::<serde_json::Value>().unwrap()["errcode"], "M_FORBIDDEN");
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_json::<MatrixError>().await.unwrap_err();
+		assert_eq!(error.status(), Some(reqwest::StatusCode::BAD_GATEWAY));
+		assert_eq!(error.json, None);
+		assert_eq!(error.inner.body.as_deref(), Some("<html>Bad Gateway</html>"));
+
+		let response = client.get(&url).send().await.unwrap();
+		let response = response.error_for_status_with_json::<MatrixError>().await.unwrap();
+		assert_eq!(response.text().await.unwrap(), "ok");
+	});
+}
+
+#[test]
+fn test_error_body_limit() {
+	/// Implements only the required method
+	struct Prefetched(reqwest::Response, &'static str);
+	impl ErrorForStatusWithBody for Prefetched {
+		async fn error_for_status_with_body(
+			self,
+		) -> Result<reqwest::Response, ReqwestErrorWithBody> {
+			let error = self.0.error_for_status_ref().unwrap_err();
+			Err(ReqwestErrorWithBody {
+				error,
+				body: Some(self.1.to_owned()),
+				body_truncated: false,
+				attempts: 1,
+			})
+		}
+	}
+
+	let client = reqwest::Client::new();
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	runtime.block_on(async {
+		let large = "a".repeat(DEFAULT_ERROR_BODY_LIMIT * 2);
+		let (url, _) = serve_responses(vec![
+			http_response("500 Internal Server Error", "", &large),
+			http_response("500 Internal Server Error", "content-type: text/plain\r\n", "aéé"),
+			http_response("500 Internal Server Error", "", "short"),
+			http_response("500 Internal Server Error", "content-type: image/png\r\n", "\u{89}PNG"),
+			http_response(
+				"500 Internal Server Error",
+				"content-type: application/problem+json; charset=utf-8\r\n",
+				"{}",
+			),
+			// Connection closed before the announced length
+			"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 100\r\nconnection: \
+			 close\r\n\r\npartial"
+				.to_owned(),
+		])
+		.await;
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body().await.unwrap_err();
+		assert_eq!(error.body.as_deref(), Some(&large[..DEFAULT_ERROR_BODY_LIMIT]));
+		assert!(error.body_truncated);
+		assert!(error.to_string().ends_with("a [truncated]"));
+
+		// The second `é` doesn't fit and the first one is not split
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body_limit(4).await.unwrap_err();
+		assert_eq!((error.body.as_deref(), error.body_truncated), (Some("aé"), true));
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body_limit(5).await.unwrap_err();
+		assert_eq!((error.body.as_deref(), error.body_truncated), (Some("short"), false));
+		assert!(error.to_string().ends_with("with body short"));
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body().await.unwrap_err();
+		assert_eq!(error.body.as_deref(), Some("<5 bytes of image/png>"));
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body().await.unwrap_err();
+		assert_eq!(error.body.as_deref(), Some("{}"));
+
+		let response = client.get(&url).send().await.unwrap();
+		let error = response.error_for_status_with_body().await.unwrap_err();
+		assert_eq!((error.body.as_deref(), error.body_truncated), (Some("partial"), true));
+	});
+
+	runtime.block_on(async {
+		let (url, _) =
+			serve_responses(vec![http_response("500 Internal Server Error", "", ""); 2]).await;
+		let response = client.get(&url).send().await.unwrap();
+		let error = Prefetched(response, "aéé").error_for_status_with_body_limit(4).await;
+		let error = error.unwrap_err();
+		assert_eq!((error.body.as_deref(), error.body_truncated), (Some("aé"), true));
+		let response = client.get(&url).send().await.unwrap();
+		let error = Prefetched(response, "short").error_for_status_with_body_limit(5).await;
+		let error = error.unwrap_err();
+		assert_eq!((error.body.as_deref(), error.body_truncated), (Some("short"), false));
+	});
+}
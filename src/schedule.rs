@@ -0,0 +1,413 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recurring schedule for periodic jobs, configured as a cron expression or a
+//! simple interval
+//!
+//! ```
+//! use famedly_rust_utils::{schedule::Schedule, timestamp::Timestamp};
+//! let schedule: Schedule =
+//! 	serde_json::from_str(r#""30 3 * * MON-FRI""#).unwrap();
+//! let now: Timestamp = "2024-01-05T04:00:00Z".parse().unwrap();
+//! assert_eq!(
+//! 	schedule.next_after(now).unwrap().to_string(),
+//! 	"2024-01-08T03:30:00Z"
+//! );
+//!
+//! let schedule: Schedule = "every 15m".parse().unwrap();
+//! assert_eq!(
+//! 	schedule.next_after(now).unwrap().to_string(),
+//! 	"2024-01-05T04:15:00Z"
+//! );
+//! ```
+
+use core::{fmt, str};
+
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::timestamp::Timestamp;
+
+const MONTHS: &[&str] =
+	&["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAYS: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Recurring schedule. Deserializes from either
+/// - a cron expression `minute hour day-of-month month day-of-week` evaluated
+///   in UTC, supporting `*`, lists, ranges, steps and month/weekday names, e.g.
+///   `*/10 8-18 * * MON-FRI`, as well as `@yearly`, `@monthly`, `@weekly`,
+///   `@daily` and `@hourly`. Like in Vixie cron, if both day-of-month and
+///   day-of-week are restricted, a day matching either of them fires.
+/// - an interval `every <n><unit>` with units `s`, `m`, `h` and `d`, e.g.
+///   `every 15m`. Firings are aligned to the Unix epoch, i.e. `every 15m` fires
+///   at `:00`, `:15`, `:30` and `:45` of every hour.
+///
+/// Serializes back to the original string.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Schedule {
+	source: String,
+	kind: Kind,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Kind {
+	Cron(Cron),
+	/// Interval in seconds
+	Every(i64),
+}
+
+/// Bit sets of allowed values for each field
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Cron {
+	minutes: u64,
+	hours: u64,
+	days: u64,
+	months: u64,
+	weekdays: u64,
+	/// Day-of-month and day-of-week fields both don't start with `*`
+	days_or_weekdays: bool,
+}
+
+impl Schedule {
+	/// First firing strictly after `ts`, in UTC. [`None`] if the schedule never
+	/// fires again (e.g. `0 0 30 2 *`) or the result is out of range.
+	#[must_use]
+	pub fn next_after(&self, ts: Timestamp) -> Option<Timestamp> {
+		match &self.kind {
+			Kind::Cron(cron) => cron.next_after(ts.to_offset(UtcOffset::UTC)),
+			Kind::Every(secs) => OffsetDateTime::from_unix_timestamp(
+				ts.unix_timestamp().div_euclid(*secs).checked_add(1)?.checked_mul(*secs)?,
+			)
+			.ok(),
+		}
+		.map(Timestamp::from)
+	}
+
+	/// First firing after [`Timestamp::now`]
+	#[must_use]
+	pub fn next(&self) -> Option<Timestamp> {
+		self.next_after(Timestamp::now())
+	}
+}
+
+impl Cron {
+	fn next_after(&self, ts: OffsetDateTime) -> Option<OffsetDateTime> {
+		let mut date = ts.date();
+		let mut hour = ts.hour();
+		let mut minute = ts.minute() + 1;
+		// Long enough to reach the next Feb 29th
+		let last_year = date.year() + 8;
+		loop {
+			if date.year() > last_year {
+				return None;
+			}
+			if !contains(self.months, date.month() as u8) {
+				date = first_of_next_month(date)?;
+				(hour, minute) = (0, 0);
+				continue;
+			}
+			if !self.day_matches(date) {
+				date = date.next_day()?;
+				(hour, minute) = (0, 0);
+				continue;
+			}
+			let Some(next_hour) = next_from(self.hours, hour) else {
+				date = date.next_day()?;
+				(hour, minute) = (0, 0);
+				continue;
+			};
+			if next_hour != hour {
+				(hour, minute) = (next_hour, 0);
+			}
+			let Some(minute) = next_from(self.minutes, minute) else {
+				(hour, minute) = (hour + 1, 0);
+				continue;
+			};
+			let time = Time::from_hms(hour, minute, 0).ok()?;
+			return Some(PrimitiveDateTime::new(date, time).assume_utc());
+		}
+	}
+
+	fn day_matches(&self, date: Date) -> bool {
+		let day = contains(self.days, date.day());
+		let weekday = contains(self.weekdays, date.weekday().number_days_from_sunday());
+		if self.days_or_weekdays {
+			day || weekday
+		} else {
+			day && weekday
+		}
+	}
+}
+
+fn contains(bits: u64, value: u8) -> bool {
+	bits & (1 << value) != 0
+}
+
+/// Smallest value in `bits` that is `>= from`
+fn next_from(bits: u64, from: u8) -> Option<u8> {
+	let bits = bits.checked_shr(from.into())?.checked_shl(from.into())?;
+	(bits != 0).then(|| bits.trailing_zeros() as u8)
+}
+
+fn first_of_next_month(date: Date) -> Option<Date> {
+	match date.month() {
+		Month::December => Date::from_calendar_date(date.year() + 1, Month::January, 1).ok(),
+		month => Date::from_calendar_date(date.year(), month.next(), 1).ok(),
+	}
+}
+
+impl fmt::Display for Schedule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		f.write_str(&self.source)
+	}
+}
+
+impl str::FromStr for Schedule {
+	type Err = ScheduleParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let trimmed = s.trim();
+		let kind = if let Some(interval) = trimmed.strip_prefix("every ") {
+			Kind::Every(parse_interval(interval.trim()).ok_or(ScheduleParseError::Interval)?)
+		} else {
+			let expression = match trimmed {
+				"@yearly" | "@annually" => "0 0 1 1 *",
+				"@monthly" => "0 0 1 * *",
+				"@weekly" => "0 0 * * 0",
+				"@daily" | "@midnight" => "0 0 * * *",
+				"@hourly" => "0 * * * *",
+				expression => expression,
+			};
+			Kind::Cron(parse_cron(expression)?)
+		};
+		Ok(Self { source: s.to_owned(), kind })
+	}
+}
+
+/// Parses `<n><unit>` into seconds
+fn parse_interval(s: &str) -> Option<i64> {
+	let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit())?);
+	let unit = match unit {
+		"s" => 1,
+		"m" => 60,
+		"h" => 60 * 60,
+		"d" => 24 * 60 * 60,
+		_ => return None,
+	};
+	number.parse::<i64>().ok()?.checked_mul(unit).filter(|secs| *secs > 0)
+}
+
+fn parse_cron(s: &str) -> Result<Cron, ScheduleParseError> {
+	let fields: Vec<&str> = s.split_whitespace().collect();
+	let [minutes, hours, days, months, weekdays] = fields[..] else {
+		return Err(ScheduleParseError::FieldCount);
+	};
+	let field = |s, min, max, names, name| {
+		parse_field(s, min, max, names).ok_or(ScheduleParseError::Field(name))
+	};
+	let mut cron = Cron {
+		minutes: field(minutes, 0, 59, &[], "minute")?,
+		hours: field(hours, 0, 23, &[], "hour")?,
+		days: field(days, 1, 31, &[], "day of month")?,
+		months: field(months, 1, 12, MONTHS, "month")?,
+		weekdays: field(weekdays, 0, 7, WEEKDAYS, "day of week")?,
+		days_or_weekdays: !days.starts_with('*') && !weekdays.starts_with('*'),
+	};
+	// Both 0 and 7 are Sunday
+	if contains(cron.weekdays, 7) {
+		cron.weekdays |= 1;
+	}
+	Ok(cron)
+}
+
+/// Parses comma separated list of `*`, `n` or `n-m` with optional `/step` into
+/// a bit set. `names` are alternatives for the values starting at `min`.
+fn parse_field(s: &str, min: u8, max: u8, names: &[&str]) -> Option<u64> {
+	let value = |s: &str| {
+		s.parse().ok().or_else(|| {
+			let index = names.iter().position(|name| name.eq_ignore_ascii_case(s))?;
+			Some(min + u8::try_from(index).ok()?)
+		})
+	};
+	let mut bits = 0;
+	for item in s.split(',') {
+		let (range, step) = match item.split_once('/') {
+			Some((range, step)) => (range, Some(step.parse::<usize>().ok().filter(|s| *s > 0)?)),
+			None => (item, None),
+		};
+		let (start, end) = match range.split_once('-') {
+			_ if range == "*" => (min, max),
+			Some((start, end)) => (value(start)?, value(end)?),
+			// `n/step` means `n-max/step`
+			None if step.is_some() => (value(range)?, max),
+			None => (value(range)?, value(range)?),
+		};
+		if start < min || end > max || start > end {
+			return None;
+		}
+		for value in (start..=end).step_by(step.unwrap_or(1)) {
+			bits |= 1 << value;
+		}
+	}
+	Some(bits)
+}
+
+impl Serialize for Schedule {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.source)
+	}
+}
+
+impl<'de> Deserialize<'de> for Schedule {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for Schedule {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"Schedule".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "Cron expression `minute hour day-of-month month day-of-week` evaluated in UTC, or interval `every <n><s|m|h|d>`",
+			"examples": ["0 3 * * *", "*/10 8-18 * * MON-FRI", "@daily", "every 15m"],
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Parsing error for [`Schedule`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleParseError {
+	/// `every` is not followed by a positive `<n><unit>` interval
+	Interval,
+	/// Cron expression doesn't have exactly 5 fields
+	FieldCount,
+	/// Cron field with the given name is invalid
+	Field(&'static str),
+}
+
+impl fmt::Display for ScheduleParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self {
+			Self::Interval => write!(f, "invalid schedule interval, expected e.g. `every 15m`"),
+			Self::FieldCount => write!(f, "cron expression must have 5 fields"),
+			Self::Field(name) => write!(f, "invalid cron {name} field"),
+		}
+	}
+}
+
+impl std::error::Error for ScheduleParseError {}
+
+#[cfg(test)]
+fn next_firings(schedule: &str, from: &str, n: usize) -> Vec<String> {
+	let schedule: Schedule = schedule.parse().unwrap();
+	let mut ts: Timestamp = from.parse().unwrap();
+	(0..n)
+		.map(|_| {
+			ts = schedule.next_after(ts).unwrap();
+			ts.to_string()
+		})
+		.collect()
+}
+
+#[test]
+fn test_cron() {
+	assert_eq!(
+		next_firings("0 3 * * *", "2024-01-01T03:00:00Z", 2),
+		["2024-01-02T03:00:00Z", "2024-01-03T03:00:00Z"]
+	);
+	assert_eq!(
+		next_firings("*/20 8-9 * * *", "2024-01-01T09:30:00.5Z", 4),
+		[
+			"2024-01-01T09:40:00Z",
+			"2024-01-02T08:00:00Z",
+			"2024-01-02T08:20:00Z",
+			"2024-01-02T08:40:00Z"
+		]
+	);
+	// Input offset is respected, output is in UTC
+	assert_eq!(next_firings("@hourly", "2024-01-01T12:59:59+02:00", 1), ["2024-01-01T11:00:00Z"]);
+	assert_eq!(next_firings("@yearly", "2024-06-01T00:00:00Z", 1), ["2025-01-01T00:00:00Z"]);
+	assert_eq!(next_firings("0 0 29 feb *", "2097-01-01T00:00:00Z", 1), ["2104-02-29T00:00:00Z"]);
+	assert_eq!(
+		next_firings("5 4 31 * *", "2024-01-31T04:05:00Z", 2),
+		["2024-03-31T04:05:00Z", "2024-05-31T04:05:00Z"]
+	);
+	assert_eq!(
+		next_firings("0 12 * Feb-Jun/2 sun,7", "2024-01-01T00:00:00Z", 2),
+		["2024-02-04T12:00:00Z", "2024-02-11T12:00:00Z"]
+	);
+	// Restricted day of month and day of week fire on either
+	assert_eq!(
+		next_firings("0 0 1 * FRI", "2024-08-31T00:00:00Z", 3),
+		["2024-09-01T00:00:00Z", "2024-09-06T00:00:00Z", "2024-09-13T00:00:00Z"]
+	);
+	assert_eq!(
+		next_firings("0 0 */10 * *", "2024-01-01T00:00:00Z", 3),
+		["2024-01-11T00:00:00Z", "2024-01-21T00:00:00Z", "2024-01-31T00:00:00Z"]
+	);
+
+	let never: Schedule = "0 0 30 2 *".parse().unwrap();
+	assert_eq!(never.next_after("2024-01-01T00:00:00Z".parse().unwrap()), None);
+}
+
+#[test]
+fn test_every() {
+	assert_eq!(
+		next_firings("every 15m", "2024-01-01T12:07:30Z", 3),
+		["2024-01-01T12:15:00Z", "2024-01-01T12:30:00Z", "2024-01-01T12:45:00Z"]
+	);
+	assert_eq!(next_firings("every 1d", "2024-01-01T12:00:00+02:00", 1), ["2024-01-02T00:00:00Z"]);
+	assert_eq!(next_firings(" every 30s ", "1969-12-31T23:59:45Z", 1), ["1970-01-01T00:00:00Z"]);
+}
+
+#[test]
+fn test_parse_errors() {
+	for (input, error) in [
+		("every", ScheduleParseError::FieldCount),
+		("every 0m", ScheduleParseError::Interval),
+		("every 15", ScheduleParseError::Interval),
+		("every m", ScheduleParseError::Interval),
+		("every 15w", ScheduleParseError::Interval),
+		("every -1h", ScheduleParseError::Interval),
+		("* * * *", ScheduleParseError::FieldCount),
+		("@reboot", ScheduleParseError::FieldCount),
+		("60 * * * *", ScheduleParseError::Field("minute")),
+		("* 5-2 * * *", ScheduleParseError::Field("hour")),
+		("* * 0 * *", ScheduleParseError::Field("day of month")),
+		("* * * foo *", ScheduleParseError::Field("month")),
+		("* * * * 8", ScheduleParseError::Field("day of week")),
+		("*/0 * * * *", ScheduleParseError::Field("minute")),
+		("1,,2 * * * *", ScheduleParseError::Field("minute")),
+	] {
+		assert_eq!(input.parse::<Schedule>(), Err(error), "{input}");
+	}
+}
+
+#[test]
+fn test_serde() {
+	for input in ["*/5 * * * MON-FRI", "every 15m", "@daily"] {
+		let schedule: Schedule = serde_json::from_value(serde_json::json!(input)).unwrap();
+		assert_eq!(serde_json::to_value(&schedule).unwrap(), serde_json::json!(input));
+	}
+	assert!(serde_json::from_value::<Schedule>(serde_json::json!("every day")).is_err());
+}
+
+#[test]
+#[cfg(feature = "schemars")]
+fn test_schemars() {
+	#[derive(schemars::JsonSchema)]
+	struct TestStruct {
+		_a: Schedule,
+	}
+	let schema = schemars::schema_for!(TestStruct);
+	assert_eq!(schema.as_value()["properties"]["_a"]["type"], "string");
+}
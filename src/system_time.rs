@@ -0,0 +1,377 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wrapper over [`SystemTime`] with RFC3339 JSON representation that doesn't
+//! require the `time` crate. Use [`crate::timestamp::Timestamp`] if you need
+//! offsets other than UTC or calendar arithmetic.
+//!
+//! ```
+//! use famedly_rust_utils::system_time::SystemTimestamp;
+//! let ts: SystemTimestamp =
+//! 	serde_json::from_str(r#""2024-01-01T14:00:00.5+02:00""#).unwrap();
+//! assert_eq!(ts.to_string(), "2024-01-01T12:00:00.5Z");
+//! ```
+
+use std::{
+	fmt, str,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// [`SystemTime`] with RFC3339 JSON representation. Always formatted in UTC,
+/// parsing accepts any offset. Parsing and serialization are limited to years
+/// `0000..=9999` and leap seconds are rejected. [`Display`](fmt::Display)
+/// prints other years in the ISO 8601 expanded format, e.g.
+/// `+010000-01-01T00:00:00Z`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(transparent)]
+pub struct SystemTimestamp {
+	ts: SystemTime,
+}
+
+impl SystemTimestamp {
+	/// Current time
+	#[must_use]
+	pub fn now() -> Self {
+		SystemTime::now().into()
+	}
+}
+
+impl AsRef<SystemTime> for SystemTimestamp {
+	fn as_ref(&self) -> &SystemTime {
+		&self.ts
+	}
+}
+
+impl std::ops::Deref for SystemTimestamp {
+	type Target = SystemTime;
+	fn deref(&self) -> &Self::Target {
+		&self.ts
+	}
+}
+
+impl From<SystemTime> for SystemTimestamp {
+	fn from(ts: SystemTime) -> Self {
+		Self { ts }
+	}
+}
+
+impl From<SystemTimestamp> for SystemTime {
+	fn from(ts: SystemTimestamp) -> Self {
+		ts.ts
+	}
+}
+
+#[cfg(feature = "time")]
+impl From<crate::timestamp::Timestamp> for SystemTimestamp {
+	fn from(ts: crate::timestamp::Timestamp) -> Self {
+		Self { ts: time::OffsetDateTime::from(ts).into() }
+	}
+}
+
+#[cfg(feature = "time")]
+impl From<SystemTimestamp> for crate::timestamp::Timestamp {
+	fn from(ts: SystemTimestamp) -> Self {
+		time::OffsetDateTime::from(ts.ts).into()
+	}
+}
+
+impl fmt::Display for SystemTimestamp {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let (secs, nanos) = to_unix(self.ts).ok_or(fmt::Error)?;
+		let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+		if (0..=9999).contains(&year) {
+			write!(f, "{year:04}")?;
+		} else {
+			write!(f, "{year:+07}")?;
+		}
+		let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+		write!(
+			f,
+			"-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+			secs_of_day / 3600,
+			secs_of_day / 60 % 60,
+			secs_of_day % 60
+		)?;
+		if nanos != 0 {
+			let fraction = format!("{nanos:09}");
+			write!(f, ".{}", fraction.trim_end_matches('0'))?;
+		}
+		f.write_str("Z")
+	}
+}
+
+impl str::FromStr for SystemTimestamp {
+	type Err = SystemTimestampParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		parse_rfc3339(s.as_bytes()).map(Self::from).map_err(SystemTimestampParseError)
+	}
+}
+
+impl Serialize for SystemTimestamp {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let (secs, _) =
+			to_unix(self.ts).ok_or_else(|| ser::Error::custom("timestamp out of range"))?;
+		let (year, ..) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+		if !(0..=9999).contains(&year) {
+			return Err(ser::Error::custom("year out of range"));
+		}
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for SystemTimestamp {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for SystemTimestamp {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"Timestamp".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"format": "date-time",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Parsing error for [`SystemTimestamp`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemTimestampParseError(&'static str);
+
+impl fmt::Display for SystemTimestampParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "invalid RFC3339 timestamp: {}", self.0)
+	}
+}
+
+impl std::error::Error for SystemTimestampParseError {}
+
+/// Splits [`SystemTime`] into seconds since the Unix epoch (negative before the
+/// epoch) and non-negative nanoseconds
+fn to_unix(ts: SystemTime) -> Option<(i64, u32)> {
+	match ts.duration_since(UNIX_EPOCH) {
+		Ok(d) => Some((i64::try_from(d.as_secs()).ok()?, d.subsec_nanos())),
+		Err(e) => {
+			let d = e.duration();
+			let secs = i64::try_from(d.as_secs()).ok()?;
+			if d.subsec_nanos() == 0 {
+				Some((-secs, 0))
+			} else {
+				Some((-secs - 1, NANOS_PER_SEC - d.subsec_nanos()))
+			}
+		}
+	}
+}
+
+/// Inverse of [`to_unix`]
+fn from_unix(secs: i64, nanos: u32) -> Option<SystemTime> {
+	if secs >= 0 {
+		UNIX_EPOCH.checked_add(Duration::new(secs.unsigned_abs(), nanos))
+	} else {
+		UNIX_EPOCH
+			.checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+			.checked_add(Duration::from_nanos(u64::from(nanos)))
+	}
+}
+
+/// Days since the Unix epoch to `(year, month, day)`, see
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+	let days = days + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days - era * 146_097;
+	let year_of_era =
+		(day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let shifted_month = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+	let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+	(year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// `(year, month, day)` to days since the Unix epoch, see
+/// <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let year_of_era = year - era * 400;
+	let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	era * 146_097 + day_of_era - 719_468
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+	match month {
+		2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+/// Parses fixed width decimal number
+fn digits(s: &[u8]) -> Option<i64> {
+	s.iter().try_fold(0, |acc, c| c.is_ascii_digit().then(|| acc * 10 + i64::from(c - b'0')))
+}
+
+/// Parses `YYYY-MM-DD(T|t| )hh:mm:ss[.f+](Z|z|(+|-)hh:mm)`
+fn parse_rfc3339(s: &[u8]) -> Result<SystemTime, &'static str> {
+	let field = |range: std::ops::Range<usize>, name| s.get(range).and_then(digits).ok_or(name);
+	let separator = |i: usize, c: &[u8]| match s.get(i) {
+		Some(x) if c.contains(x) => Ok(()),
+		_ => Err("unexpected separator"),
+	};
+
+	let year = field(0..4, "invalid year")?;
+	separator(4, b"-")?;
+	let month = field(5..7, "invalid month")?;
+	separator(7, b"-")?;
+	let day = field(8..10, "invalid day")?;
+	separator(10, b"Tt ")?;
+	let hour = field(11..13, "invalid hour")?;
+	separator(13, b":")?;
+	let minute = field(14..16, "invalid minute")?;
+	separator(16, b":")?;
+	let second = field(17..19, "invalid second")?;
+
+	if !(1..=12).contains(&month) {
+		return Err("month out of range");
+	}
+	if !(1..=days_in_month(year, month)).contains(&day) {
+		return Err("day out of range");
+	}
+	if hour > 23 || minute > 59 || second > 59 {
+		return Err("time out of range");
+	}
+
+	let mut rest = &s[19..];
+	let mut nanos = 0;
+	if let Some(fraction) = rest.strip_prefix(b".") {
+		let len = fraction.iter().take_while(|c| c.is_ascii_digit()).count();
+		if len == 0 {
+			return Err("empty fraction");
+		}
+		// Digits beyond nanosecond precision are truncated
+		let padded = format!("{:0<9}", String::from_utf8_lossy(&fraction[..len.min(9)]));
+		nanos = digits(padded.as_bytes())
+			.and_then(|x| u32::try_from(x).ok())
+			.ok_or("invalid fraction")?;
+		rest = &fraction[len..];
+	}
+
+	let offset = match rest {
+		b"Z" | b"z" => 0,
+		[sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+			let hours = digits(&[*h1, *h2]).ok_or("invalid offset")?;
+			let minutes = digits(&[*m1, *m2]).ok_or("invalid offset")?;
+			if hours > 23 || minutes > 59 {
+				return Err("offset out of range");
+			}
+			let offset = hours * 3600 + minutes * 60;
+			if *sign == b'-' {
+				-offset
+			} else {
+				offset
+			}
+		}
+		_ => return Err("invalid offset"),
+	};
+
+	let secs =
+		days_from_civil(year, month, day) * SECS_PER_DAY + hour * 3600 + minute * 60 + second
+			- offset;
+	from_unix(secs, nanos).ok_or("timestamp out of range")
+}
+
+#[test]
+fn test_roundtrip() {
+	for (input, expected) in [
+		("1970-01-01T00:00:00Z", "1970-01-01T00:00:00Z"),
+		("2024-02-29T23:59:59.999999999Z", "2024-02-29T23:59:59.999999999Z"),
+		("2024-01-01t14:00:00.5+02:00", "2024-01-01T12:00:00.5Z"),
+		("2024-01-01 00:30:00-01:00", "2024-01-01T01:30:00Z"),
+		("1969-12-31T23:59:59.25Z", "1969-12-31T23:59:59.25Z"),
+		("1900-03-01T00:00:00Z", "1900-03-01T00:00:00Z"),
+		("0000-01-01T00:00:00Z", "0000-01-01T00:00:00Z"),
+		("9999-12-31T23:59:59Z", "9999-12-31T23:59:59Z"),
+		("2024-01-01T00:00:00.1234567891Z", "2024-01-01T00:00:00.123456789Z"),
+	] {
+		let ts: SystemTimestamp = input.parse().unwrap();
+		assert_eq!(ts.to_string(), expected, "{input}");
+	}
+
+	let ts: SystemTimestamp = "1970-01-01T00:00:01.5Z".parse().unwrap();
+	assert_eq!(*ts, UNIX_EPOCH + Duration::from_millis(1500));
+	assert_eq!(serde_json::to_value(ts).unwrap(), serde_json::json!("1970-01-01T00:00:01.5Z"));
+	let parsed: SystemTimestamp =
+		serde_json::from_value(serde_json::json!("1970-01-01T00:00:01.5Z")).unwrap();
+	assert_eq!(parsed, ts);
+}
+
+#[test]
+fn test_out_of_range_year() {
+	// 10000-01-01T00:00:00Z
+	let ts = SystemTimestamp::from(UNIX_EPOCH + Duration::from_secs(253_402_300_800));
+	assert_eq!(ts.to_string(), "+010000-01-01T00:00:00Z");
+	let error = serde_json::to_value(ts).unwrap_err();
+	assert_eq!(error.to_string(), "year out of range");
+
+	// One second before 0000-01-01T00:00:00Z
+	let ts = SystemTimestamp::from(UNIX_EPOCH - Duration::from_secs(62_167_219_201));
+	assert_eq!(ts.to_string(), "-000001-12-31T23:59:59Z");
+	assert!(serde_json::to_value(ts).is_err());
+	assert!(ts.to_string().parse::<SystemTimestamp>().is_err());
+}
+
+#[test]
+fn test_invalid() {
+	for input in [
+		"",
+		"2024-01-01",
+		"2024-01-01T12:00:00",
+		"2024-13-01T12:00:00Z",
+		"2023-02-29T12:00:00Z",
+		"2024-01-01T24:00:00Z",
+		"2024-01-01T23:59:60Z",
+		"2024-01-01T12:00:00.Z",
+		"2024-01-01T12:00:00+0200",
+		"2024-01-01T12:00:00+24:00",
+		"2024-01-01X12:00:00Z",
+		"+024-01-01T12:00:00Z",
+		"2024-01-01T12:00:00Z ",
+	] {
+		assert!(input.parse::<SystemTimestamp>().is_err(), "{input}");
+	}
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_consistent_with_timestamp() {
+	use crate::timestamp::Timestamp;
+
+	let mut secs = -62_167_219_200; // 0000-01-01T00:00:00Z
+	while secs < 253_402_300_800 {
+		// 10000-01-01T00:00:00Z
+		let ts = SystemTimestamp::from(from_unix(secs, 123_000_000).unwrap());
+		let expected = Timestamp::from(ts);
+		assert_eq!(ts.to_string(), expected.to_string());
+		assert_eq!(ts.to_string().parse::<Timestamp>().unwrap(), expected);
+		assert_eq!(SystemTimestamp::from(expected), ts);
+		secs += 7_777_777;
+	}
+}
@@ -0,0 +1,379 @@
+// SPDX-FileCopyrightText: 2026 Famedly GmbH (info@famedly.com)
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Half-open time window `[start, end)` over [`Timestamp`]s with RFC3339
+//! interval JSON representation
+//!
+//! ```
+//! use famedly_rust_utils::time_range::TimeRange;
+//! let range: TimeRange =
+//! 	serde_json::from_str(r#""2024-01-01T12:00:00Z/PT1H30M""#).unwrap();
+//! assert_eq!(range.to_string(), "2024-01-01T12:00:00Z/2024-01-01T13:30:00Z");
+//! ```
+
+use core::{fmt, str};
+
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use time::Duration;
+
+use crate::timestamp::Timestamp;
+
+/// Half-open time window `[start, end)`, `start` is guaranteed to not be after
+/// `end`. Represented as `<start>/<end>` or `<start>/<duration>` string, where
+/// `start` and `end` are RFC3339 timestamps and `duration` is an ISO 8601
+/// duration in weeks, days, hours, minutes and seconds (e.g. `P1DT12H`,
+/// `PT0.5S`). Years and months are not supported as their length depends on the
+/// calendar. Always serialized as `<start>/<end>`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TimeRange {
+	start: Timestamp,
+	end: Timestamp,
+}
+
+impl TimeRange {
+	/// Fails if `start` is after `end`
+	pub fn new(start: Timestamp, end: Timestamp) -> Result<Self, InvalidTimeRange> {
+		if start > end {
+			return Err(InvalidTimeRange { start, end: Some(end) });
+		}
+		Ok(Self { start, end })
+	}
+
+	/// Range of `duration` length beginning at `start`. Fails if `duration` is
+	/// negative or the end is out of the supported timestamp range.
+	pub fn with_duration(start: Timestamp, duration: Duration) -> Result<Self, InvalidTimeRange> {
+		let end = start.checked_add(duration).ok_or(InvalidTimeRange { start, end: None })?;
+		Self::new(start, end.into())
+	}
+
+	/// Inclusive start of the range
+	#[must_use]
+	pub const fn start(&self) -> Timestamp {
+		self.start
+	}
+
+	/// Exclusive end of the range
+	#[must_use]
+	pub const fn end(&self) -> Timestamp {
+		self.end
+	}
+
+	/// Length of the range
+	#[must_use]
+	pub fn duration(&self) -> Duration {
+		self.end - self.start
+	}
+
+	/// Whether the range has zero length and thus contains nothing
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.start == self.end
+	}
+
+	/// Whether `ts` is within `[start, end)`
+	#[must_use]
+	pub fn contains(&self, ts: Timestamp) -> bool {
+		self.start <= ts && ts < self.end
+	}
+
+	/// Whether both ranges share at least one instant. Adjacent ranges like
+	/// `[a, b)` and `[b, c)` don't overlap.
+	#[must_use]
+	pub fn overlaps(&self, other: &Self) -> bool {
+		self.start < other.end && other.start < self.end
+	}
+
+	/// Common part of both ranges, [`None`] if they don't
+	/// [overlap](Self::overlaps)
+	#[must_use]
+	pub fn intersection(&self, other: &Self) -> Option<Self> {
+		self.overlaps(other)
+			.then(|| Self { start: self.start.max(other.start), end: self.end.min(other.end) })
+	}
+
+	/// Iterates over `start`, `start + step`, `start + 2 * step`, ... while
+	/// before `end`
+	///
+	/// # Panics
+	/// If `step` is not positive
+	#[must_use]
+	pub fn step_by(&self, step: Duration) -> TimeRangeSteps {
+		assert!(step.is_positive(), "time range step must be positive");
+		TimeRangeSteps { next: Some(self.start), end: self.end, step }
+	}
+}
+
+/// Iterator returned by [`TimeRange::step_by`]
+#[derive(Debug, Clone)]
+pub struct TimeRangeSteps {
+	next: Option<Timestamp>,
+	end: Timestamp,
+	step: Duration,
+}
+
+impl Iterator for TimeRangeSteps {
+	type Item = Timestamp;
+	fn next(&mut self) -> Option<Timestamp> {
+		let current = self.next.filter(|ts| *ts < self.end)?;
+		self.next = current.checked_add(self.step).map(Timestamp::from);
+		Some(current)
+	}
+}
+
+impl fmt::Display for TimeRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "{}/{}", self.start, self.end)
+	}
+}
+
+impl str::FromStr for TimeRange {
+	type Err = TimeRangeParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (start, end) = s.split_once('/').ok_or(TimeRangeParseError::Format)?;
+		let start: Timestamp = start.parse()?;
+		let end = if end.starts_with('P') {
+			let duration = parse_iso8601_duration(end).ok_or(TimeRangeParseError::Duration)?;
+			start.checked_add(duration).ok_or(TimeRangeParseError::Duration)?.into()
+		} else {
+			end.parse()?
+		};
+		Ok(Self::new(start, end)?)
+	}
+}
+
+impl Serialize for TimeRange {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for TimeRange {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for TimeRange {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"TimeRange".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "Time interval `<start>/<end>` or `<start>/<duration>` with RFC3339 timestamps and ISO 8601 duration",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Error for a [`TimeRange`] with `start` after `end` or an end out of the
+/// supported timestamp range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTimeRange {
+	/// Requested start
+	pub start: Timestamp,
+	/// Requested end, `None` if it is out of range
+	pub end: Option<Timestamp>,
+}
+
+impl fmt::Display for InvalidTimeRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self.end {
+			Some(end) => write!(f, "time range start {} is after its end {end}", self.start),
+			None => write!(f, "time range starting at {} ends out of range", self.start),
+		}
+	}
+}
+
+impl std::error::Error for InvalidTimeRange {}
+
+/// Parsing error for [`TimeRange`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeRangeParseError {
+	/// No `/` separator
+	Format,
+	/// Start or end is not a valid RFC3339 timestamp
+	Timestamp(time::error::Parse),
+	/// Duration is not a supported ISO 8601 duration or out of range
+	Duration,
+	/// Start is after end
+	Range(InvalidTimeRange),
+}
+
+impl fmt::Display for TimeRangeParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self {
+			Self::Format => write!(f, "time range must be `<start>/<end>` or `<start>/<duration>`"),
+			Self::Timestamp(e) => write!(f, "invalid time range timestamp: {e}"),
+			Self::Duration => write!(f, "invalid time range duration"),
+			Self::Range(e) => write!(f, "{e}"),
+		}
+	}
+}
+
+impl std::error::Error for TimeRangeParseError {}
+
+impl From<time::error::Parse> for TimeRangeParseError {
+	fn from(e: time::error::Parse) -> Self {
+		Self::Timestamp(e)
+	}
+}
+
+impl From<InvalidTimeRange> for TimeRangeParseError {
+	fn from(e: InvalidTimeRange) -> Self {
+		Self::Range(e)
+	}
+}
+
+/// Parses `P[nW][nD][T[nH][nM][n[.f]S]]`
+fn parse_iso8601_duration(s: &str) -> Option<Duration> {
+	let (date, time) = match s.strip_prefix('P')?.split_once('T') {
+		Some((_, "")) => return None,
+		Some((date, time)) => (date, time),
+		None => (s.strip_prefix('P')?, ""),
+	};
+	if date.is_empty() && time.is_empty() {
+		return None;
+	}
+	components(date, &[('W', 7 * 86400), ('D', 86400)])?
+		.checked_add(components(time, &[('H', 3600), ('M', 60), ('S', 1)])?)
+}
+
+/// Sums `<number><unit>` components of an ISO 8601 duration, `units` are
+/// `(designator, seconds)` in the order they have to appear in
+fn components(mut s: &str, mut units: &[(char, i64)]) -> Option<Duration> {
+	let mut total = Duration::ZERO;
+	while !s.is_empty() {
+		let (number, rest) = s.split_at(s.find(|c: char| !c.is_ascii_digit() && c != '.')?);
+		let designator = rest.chars().next()?;
+		let index = units.iter().position(|(unit, _)| *unit == designator)?;
+		let (whole, fraction) = match number.split_once('.') {
+			// Only the seconds may be fractional
+			Some((whole, fraction)) if designator == 'S' && !fraction.is_empty() => {
+				let fraction = &fraction[..fraction.len().min(9)];
+				(whole, format!("{fraction:0<9}").parse::<i32>().ok()?)
+			}
+			Some(_) => return None,
+			None => (number, 0),
+		};
+		let secs = whole.parse::<i64>().ok()?.checked_mul(units[index].1)?;
+		total = total.checked_add(Duration::new(secs, fraction))?;
+		units = &units[index + 1..];
+		s = &rest[1..];
+	}
+	Some(total)
+}
+
+#[cfg(test)]
+fn ts(s: &str) -> Timestamp {
+	s.parse().unwrap()
+}
+
+#[test]
+fn test_serde() {
+	for (input, expected) in [
+		("2024-01-01T12:00:00Z/2024-01-01T13:00:00Z", "2024-01-01T12:00:00Z/2024-01-01T13:00:00Z"),
+		("2024-01-01T12:00:00Z/PT1H", "2024-01-01T12:00:00Z/2024-01-01T13:00:00Z"),
+		(
+			"2024-01-01T12:00:00+02:00/P1W2DT3H4M5.5S",
+			"2024-01-01T12:00:00+02:00/2024-01-10T15:04:05.5+02:00",
+		),
+		("2024-01-01T12:00:00Z/P1D", "2024-01-01T12:00:00Z/2024-01-02T12:00:00Z"),
+		("2024-01-01T12:00:00Z/PT0S", "2024-01-01T12:00:00Z/2024-01-01T12:00:00Z"),
+		("2024-01-01T12:00:00Z/PT90M", "2024-01-01T12:00:00Z/2024-01-01T13:30:00Z"),
+	] {
+		let range: TimeRange = serde_json::from_value(serde_json::json!(input)).unwrap();
+		assert_eq!(serde_json::to_value(range).unwrap(), serde_json::json!(expected), "{input}");
+		assert_eq!(expected.parse::<TimeRange>().unwrap(), range);
+	}
+
+	for (input, expected) in [
+		("2024-01-01T12:00:00Z", TimeRangeParseError::Format),
+		(
+			"2024-01-01T13:00:00Z/2024-01-01T12:00:00Z",
+			TimeRangeParseError::Range(InvalidTimeRange {
+				start: ts("2024-01-01T13:00:00Z"),
+				end: Some(ts("2024-01-01T12:00:00Z")),
+			}),
+		),
+		("2024-01-01T12:00:00Z/P", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/PT", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/P1M", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/P1Y", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/PT1M1H", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/PT1.5H", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/PT1H ", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/P1H", TimeRangeParseError::Duration),
+		("2024-01-01T12:00:00Z/PT-1H", TimeRangeParseError::Duration),
+	] {
+		assert_eq!(input.parse::<TimeRange>(), Err(expected), "{input}");
+	}
+	assert!(matches!(
+		"2024-01-01/PT1H".parse::<TimeRange>(),
+		Err(TimeRangeParseError::Timestamp(_))
+	));
+}
+
+#[test]
+fn test_queries() {
+	let range = TimeRange::new(ts("2024-01-01T12:00:00Z"), ts("2024-01-01T14:00:00Z")).unwrap();
+	assert_eq!(range.duration(), Duration::hours(2));
+	assert!(!range.is_empty());
+	assert!(range.contains(ts("2024-01-01T12:00:00Z")));
+	assert!(range.contains(ts("2024-01-01T15:59:59+02:00")));
+	assert!(!range.contains(ts("2024-01-01T14:00:00Z")));
+	assert!(!range.contains(ts("2024-01-01T11:59:59Z")));
+
+	let later = TimeRange::with_duration(ts("2024-01-01T13:00:00Z"), Duration::hours(2)).unwrap();
+	assert!(range.overlaps(&later) && later.overlaps(&range));
+	assert_eq!(
+		range.intersection(&later).unwrap().to_string(),
+		"2024-01-01T13:00:00Z/2024-01-01T14:00:00Z"
+	);
+
+	let adjacent = TimeRange::with_duration(range.end(), Duration::hours(1)).unwrap();
+	assert!(!range.overlaps(&adjacent));
+	assert_eq!(range.intersection(&adjacent), None);
+
+	let empty = TimeRange::new(range.start(), range.start()).unwrap();
+	assert!(empty.is_empty() && !empty.contains(range.start()) && !empty.overlaps(&range));
+
+	assert!(TimeRange::with_duration(range.start(), Duration::seconds(-1)).is_err());
+	assert_eq!(
+		TimeRange::with_duration(range.start(), Duration::MAX),
+		Err(InvalidTimeRange { start: range.start(), end: None })
+	);
+}
+
+#[test]
+fn test_step_by() {
+	let range =
+		TimeRange::with_duration(ts("2024-01-01T12:00:00Z"), Duration::minutes(50)).unwrap();
+	let steps: Vec<_> = range.step_by(Duration::minutes(15)).map(|ts| ts.to_string()).collect();
+	assert_eq!(
+		steps,
+		[
+			"2024-01-01T12:00:00Z",
+			"2024-01-01T12:15:00Z",
+			"2024-01-01T12:30:00Z",
+			"2024-01-01T12:45:00Z"
+		]
+	);
+	assert_eq!(range.step_by(Duration::hours(1)).count(), 1);
+
+	let empty = TimeRange::new(range.start(), range.start()).unwrap();
+	assert_eq!(empty.step_by(Duration::minutes(1)).count(), 0);
+}
+
+#[test]
+#[should_panic = "time range step must be positive"]
+fn test_step_by_zero() {
+	let range = TimeRange::with_duration(ts("2024-01-01T12:00:00Z"), Duration::hours(1)).unwrap();
+	let _ = range.step_by(Duration::ZERO);
+}
@@ -2,19 +2,104 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-//! Wrapper over [`OffsetDateTime`] with RFC3339 JSON representation
+//! Wrappers over [`OffsetDateTime`] with various JSON representations
+//!
+//! - [`Timestamp`] - RFC3339 string
+//! - [`UtcTimestamp`] - RFC3339 string normalized to UTC with fixed sub-second
+//!   precision, see [`TimestampSecs`], [`TimestampMillis`] and
+//!   [`TimestampMicros`]
+//! - [`LenientTimestamp`] - RFC3339 string, also accepting some legacy variants
+//!   on deserialization, see [`Timestamp::parse_lenient`]
+//! - [`UnixSeconds`] - integer seconds since the Unix epoch
+//! - [`UnixMs`] - integer milliseconds since the Unix epoch (e.g. Matrix
+//!   `origin_server_ts`)
+//! - [`HttpDate`] - RFC 2822 string, formatted as IMF-fixdate (as used in HTTP
+//!   headers)
+//! - [`NaiveTimestamp`] - ISO 8601 string without offset, interpreted as UTC
+//!
+//! All of them convert into each other through [`Timestamp`].
 
 #![allow(missing_docs, unused_qualifications)]
 
 use core::{fmt, str};
+use std::{
+	cell::RefCell,
+	marker::PhantomData,
+	ops::{Add, AddAssign, Sub, SubAssign},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	time::Duration as StdDuration,
+};
 
 #[cfg(feature = "schemars")]
 use schemars::{JsonSchema, Schema, SchemaGenerator};
-use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use time::{
+	format_description::{
+		well_known::{Rfc2822, Rfc3339},
+		BorrowedFormatItem,
+	},
+	macros::format_description,
+	Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset,
+};
+
+use crate::duration::{Hours, Micros, Minutes, Ms, Nanos, Seconds};
+
+/// Shared impls for all [`OffsetDateTime`] wrappers in this module
+macro_rules! impl_timestamp_wrapper {
+	($($name:ident),*) => {
+		$(
+			impl AsRef<OffsetDateTime> for $name {
+				fn as_ref(&self) -> &OffsetDateTime {
+					&self.ts
+				}
+			}
+
+			impl std::ops::Deref for $name {
+				type Target = OffsetDateTime;
+				fn deref(&self) -> &Self::Target {
+					&self.ts
+				}
+			}
+
+			impl From<OffsetDateTime> for $name {
+				fn from(ts: OffsetDateTime) -> Self {
+					Self { ts }
+				}
+			}
+
+			impl From<$name> for OffsetDateTime {
+				fn from(ts: $name) -> Self {
+					ts.ts
+				}
+			}
+		)*
+	};
+}
+
+/// Conversions between [`Timestamp`] and its siblings
+macro_rules! impl_timestamp_conversions {
+	($($name:ident),*) => {
+		$(
+			impl From<Timestamp> for $name {
+				fn from(ts: Timestamp) -> Self {
+					Self { ts: ts.ts }
+				}
+			}
+
+			impl From<$name> for Timestamp {
+				fn from(ts: $name) -> Self {
+					Self { ts: ts.ts }
+				}
+			}
+		)*
+	};
+}
 
 /// Timestamp with RFC3339 JSON representation
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 #[serde(transparent)]
 #[repr(transparent)]
 pub struct Timestamp {
@@ -22,19 +107,6 @@ pub struct Timestamp {
 	ts: OffsetDateTime,
 }
 
-impl AsRef<OffsetDateTime> for Timestamp {
-	fn as_ref(&self) -> &OffsetDateTime {
-		&self.ts
-	}
-}
-
-impl std::ops::Deref for Timestamp {
-	type Target = OffsetDateTime;
-	fn deref(&self) -> &Self::Target {
-		&self.ts
-	}
-}
-
 impl fmt::Display for Timestamp {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
 		use time::format_description::well_known::Rfc3339;
@@ -68,14 +140,882 @@ impl JsonSchema for Timestamp {
 	}
 }
 
-impl From<OffsetDateTime> for Timestamp {
+impl Timestamp {
+	/// Current time in UTC, or the time of the [`MockClock`] installed on the
+	/// current thread
+	#[must_use]
+	pub fn now() -> Self {
+		if MOCK_CLOCKS_INSTALLED.load(Ordering::Relaxed) == 0 {
+			return OffsetDateTime::now_utc().into();
+		}
+		MOCK_CLOCK
+			.with(|clock| clock.borrow().as_ref().map(MockClock::now))
+			.unwrap_or_else(|| OffsetDateTime::now_utc().into())
+	}
+
+	/// Parses RFC3339 as well as the following variants sent by legacy clients:
+	/// - lowercase `t` and `z`, e.g. `2024-01-01t12:00:00z`
+	/// - space instead of `T`, e.g. `2024-01-01 12:00:00+02:00`
+	/// - no offset, e.g. `2024-01-01 12:00:00`, in which case `default_offset`
+	///   is assumed
+	///
+	/// Leading and trailing whitespace is ignored. Use [`str::parse`] for
+	/// strict RFC3339 parsing.
+	/// ```
+	/// # use famedly_rust_utils::timestamp::Timestamp;
+	/// # use time::macros::offset;
+	/// let ts = Timestamp::parse_lenient("2024-01-01 12:00:00", offset!(+2)).unwrap();
+	/// assert_eq!(ts.to_string(), "2024-01-01T12:00:00+02:00");
+	/// ```
+	pub fn parse_lenient(s: &str, default_offset: UtcOffset) -> Result<Self, time::error::Parse> {
+		let mut s = s.trim().to_ascii_uppercase();
+		if s.as_bytes().get(10) == Some(&b' ') {
+			s.replace_range(10..11, "T");
+		}
+		match OffsetDateTime::parse(&s, &Rfc3339) {
+			Ok(ts) => Ok(Self { ts }),
+			Err(error) => PrimitiveDateTime::parse(&s, &NAIVE_PARSE)
+				.map(|ts| Self { ts: ts.assume_offset(default_offset) })
+				.map_err(|_| error),
+		}
+	}
+}
+
+/// Arithmetic impls for [`Timestamp`], delegating to [`OffsetDateTime`]. Panic
+/// on overflow just like the latter.
+macro_rules! impl_timestamp_arithmetic {
+	($($d:ty),*; wrappers: $($w:ident),*) => {
+		$(
+			impl_timestamp_arithmetic!(@impl [], $d, rhs => rhs;);
+		)*
+		$(
+			impl_timestamp_arithmetic!(
+				@impl [D], $w<D>, rhs => rhs.0;
+				OffsetDateTime: Add<D, Output = OffsetDateTime>
+					+ AddAssign<D>
+					+ Sub<D, Output = OffsetDateTime>
+					+ SubAssign<D>
+			);
+		)*
+	};
+	(@impl [$($g:ident)?], $d:ty, $rhs:ident => $conv:expr; $($bounds:tt)*) => {
+		impl<$($g)?> Add<$d> for Timestamp where $($bounds)* {
+			type Output = Self;
+			fn add(self, $rhs: $d) -> Self {
+				Self { ts: self.ts + $conv }
+			}
+		}
+
+		impl<$($g)?> AddAssign<$d> for Timestamp where $($bounds)* {
+			fn add_assign(&mut self, $rhs: $d) {
+				self.ts += $conv;
+			}
+		}
+
+		impl<$($g)?> Sub<$d> for Timestamp where $($bounds)* {
+			type Output = Self;
+			fn sub(self, $rhs: $d) -> Self {
+				Self { ts: self.ts - $conv }
+			}
+		}
+
+		impl<$($g)?> SubAssign<$d> for Timestamp where $($bounds)* {
+			fn sub_assign(&mut self, $rhs: $d) {
+				self.ts -= $conv;
+			}
+		}
+	};
+}
+
+impl_timestamp_arithmetic!(Duration, StdDuration; wrappers: Seconds, Minutes, Hours, Ms, Micros, Nanos);
+
+impl Sub for Timestamp {
+	type Output = Duration;
+	fn sub(self, rhs: Self) -> Duration {
+		self.ts - rhs.ts
+	}
+}
+
+/// Source of the current time. Take a `Clock` in business code to be able to
+/// substitute a [`MockClock`] in tests.
+pub trait Clock: Send + Sync {
+	/// Current time
+	fn now(&self) -> Timestamp;
+}
+
+/// [`Clock`] returning [`Timestamp::now`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn now(&self) -> Timestamp {
+		Timestamp::now()
+	}
+}
+
+thread_local! {
+	static MOCK_CLOCK: RefCell<Option<MockClock>> = const { RefCell::new(None) };
+}
+
+/// Number of live [`MockClockGuard`]s on all threads, lets [`Timestamp::now`]
+/// skip the thread-local lookup when no mock is installed
+static MOCK_CLOCKS_INSTALLED: AtomicUsize = AtomicUsize::new(0);
+
+/// [`Clock`] that only moves when told to. Clones share the same time.
+///
+/// Call [`MockClock::install`] to also make [`Timestamp::now`] return the
+/// mocked time on the current thread only, e.g. in a single-threaded async
+/// test runtime. Code running on other threads, like the workers of a
+/// multi-threaded runtime, has to take a [`Clock`] instead.
+/// ```
+/// # use famedly_rust_utils::timestamp::{MockClock, Timestamp};
+/// let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+/// let _guard = clock.install();
+/// assert_eq!(Timestamp::now().to_string(), "2024-01-01T00:00:00Z");
+/// clock.advance(time::Duration::minutes(5));
+/// assert_eq!(Timestamp::now().to_string(), "2024-01-01T00:05:00Z");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+	now: Arc<Mutex<Timestamp>>,
+}
+
+impl MockClock {
+	/// Creates a clock stopped at `now`
+	#[must_use]
+	pub fn new(now: Timestamp) -> Self {
+		Self { now: Arc::new(Mutex::new(now)) }
+	}
+
+	/// Sets the current time
+	pub fn set(&self, now: Timestamp) {
+		*self.now.lock().unwrap_or_else(PoisonError::into_inner) = now;
+	}
+
+	/// Moves the current time by `duration`
+	pub fn advance(&self, duration: Duration) {
+		*self.now.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+	}
+
+	/// Makes [`Timestamp::now`] return the time of this clock on the current
+	/// thread until the returned guard is dropped. Other threads are not
+	/// affected.
+	#[must_use = "the clock is uninstalled when the guard is dropped"]
+	pub fn install(&self) -> MockClockGuard {
+		let previous = MOCK_CLOCK.with(|clock| clock.replace(Some(self.clone())));
+		MOCK_CLOCKS_INSTALLED.fetch_add(1, Ordering::Relaxed);
+		MockClockGuard { previous, _not_send: PhantomData }
+	}
+}
+
+impl Clock for MockClock {
+	fn now(&self) -> Timestamp {
+		*self.now.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+/// Guard returned by [`MockClock::install`], restores the previously installed
+/// clock on drop. It is bound to the installing thread, so it can't be held
+/// across an `.await` in a `Send` future:
+/// ```compile_fail
+/// # use famedly_rust_utils::timestamp::{MockClock, Timestamp};
+/// fn assert_send<T: Send>(_: T) {}
+/// let guard = MockClock::new(Timestamp::now()).install();
+/// assert_send(guard);
+/// ```
+#[derive(Debug)]
+pub struct MockClockGuard {
+	previous: Option<MockClock>,
+	/// Makes the guard `!Send` as it has to be dropped on the installing thread
+	_not_send: PhantomData<*const ()>,
+}
+
+impl Drop for MockClockGuard {
+	fn drop(&mut self) {
+		MOCK_CLOCK.with(|clock| clock.replace(self.previous.take()));
+		MOCK_CLOCKS_INSTALLED.fetch_sub(1, Ordering::Relaxed);
+	}
+}
+
+impl_timestamp_wrapper!(Timestamp, LenientTimestamp, UnixSeconds, UnixMs, HttpDate, NaiveTimestamp);
+impl_timestamp_conversions!(LenientTimestamp, UnixSeconds, UnixMs, HttpDate, NaiveTimestamp);
+
+/// Same as [`Timestamp`] but deserialized with [`Timestamp::parse_lenient`],
+/// assuming UTC for inputs without offset. Always serialized as RFC3339.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(transparent)]
+pub struct LenientTimestamp {
+	ts: OffsetDateTime,
+}
+
+impl fmt::Display for LenientTimestamp {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		Timestamp::from(*self).fmt(f)
+	}
+}
+
+impl str::FromStr for LenientTimestamp {
+	type Err = time::error::Parse;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Timestamp::parse_lenient(s, UtcOffset::UTC).map(Self::from)
+	}
+}
+
+impl Serialize for LenientTimestamp {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		Timestamp::from(*self).serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for LenientTimestamp {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for LenientTimestamp {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"LenientTimestamp".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "RFC3339 date and time, offset defaults to UTC",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Timestamp with RFC3339 JSON representation that is normalized to UTC and
+/// truncated to `DIGITS` fractional second digits (0, 3, 6 or 9) on
+/// construction, so every value serializes the same way:
+/// ```
+/// # use famedly_rust_utils::timestamp::{Timestamp, TimestampMillis};
+/// let ts: Timestamp = "2024-01-01T14:00:00.123456789+02:00".parse().unwrap();
+/// assert_eq!(
+/// 	TimestampMillis::from(ts).to_string(),
+/// 	"2024-01-01T12:00:00.123Z"
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[repr(transparent)]
+pub struct UtcTimestamp<const DIGITS: u8 = 9> {
+	ts: OffsetDateTime,
+}
+
+/// [`UtcTimestamp`] with second precision
+pub type TimestampSecs = UtcTimestamp<0>;
+/// [`UtcTimestamp`] with millisecond precision
+pub type TimestampMillis = UtcTimestamp<3>;
+/// [`UtcTimestamp`] with microsecond precision
+pub type TimestampMicros = UtcTimestamp<6>;
+
+impl<const DIGITS: u8> UtcTimestamp<DIGITS> {
+	const VALID_DIGITS: () =
+		assert!(matches!(DIGITS, 0 | 3 | 6 | 9), "`DIGITS` must be one of 0, 3, 6 or 9");
+
+	/// Nanoseconds per unit of precision
+	const STEP: u32 = match DIGITS {
+		0 => 1_000_000_000,
+		3 => 1_000_000,
+		6 => 1_000,
+		_ => 1,
+	};
+
+	const FORMAT: &'static [BorrowedFormatItem<'static>] = match DIGITS {
+		0 => format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z"),
+		3 => format_description!(
+			"[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+		),
+		6 => format_description!(
+			"[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]Z"
+		),
+		_ => format_description!(
+			"[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]Z"
+		),
+	};
+
+	/// Normalizes `ts` to UTC and truncates it to the precision of `DIGITS`
+	#[must_use]
+	pub fn new(ts: OffsetDateTime) -> Self {
+		let () = Self::VALID_DIGITS;
+		let ts = ts.to_offset(UtcOffset::UTC);
+		Self { ts: ts - Duration::nanoseconds(i64::from(ts.nanosecond() % Self::STEP)) }
+	}
+
+	/// Current time, see [`Timestamp::now`]
+	#[must_use]
+	pub fn now() -> Self {
+		Timestamp::now().into()
+	}
+}
+
+impl<const DIGITS: u8> AsRef<OffsetDateTime> for UtcTimestamp<DIGITS> {
+	fn as_ref(&self) -> &OffsetDateTime {
+		&self.ts
+	}
+}
+
+impl<const DIGITS: u8> std::ops::Deref for UtcTimestamp<DIGITS> {
+	type Target = OffsetDateTime;
+	fn deref(&self) -> &Self::Target {
+		&self.ts
+	}
+}
+
+impl<const DIGITS: u8> From<OffsetDateTime> for UtcTimestamp<DIGITS> {
 	fn from(ts: OffsetDateTime) -> Self {
-		Self { ts }
+		Self::new(ts)
 	}
 }
 
-impl From<Timestamp> for OffsetDateTime {
-	fn from(ts: Timestamp) -> Self {
+impl<const DIGITS: u8> From<UtcTimestamp<DIGITS>> for OffsetDateTime {
+	fn from(ts: UtcTimestamp<DIGITS>) -> Self {
 		ts.ts
 	}
 }
+
+impl<const DIGITS: u8> From<Timestamp> for UtcTimestamp<DIGITS> {
+	fn from(ts: Timestamp) -> Self {
+		Self::new(ts.ts)
+	}
+}
+
+impl<const DIGITS: u8> From<UtcTimestamp<DIGITS>> for Timestamp {
+	fn from(ts: UtcTimestamp<DIGITS>) -> Self {
+		Self { ts: ts.ts }
+	}
+}
+
+impl<const DIGITS: u8> fmt::Display for UtcTimestamp<DIGITS> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let ts = self.ts.format(&Self::FORMAT).map_err(|_| fmt::Error)?;
+		write!(f, "{}", ts)
+	}
+}
+
+impl<const DIGITS: u8> str::FromStr for UtcTimestamp<DIGITS> {
+	type Err = time::error::Parse;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Timestamp::from_str(s)?.into())
+	}
+}
+
+impl<const DIGITS: u8> Serialize for UtcTimestamp<DIGITS> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de, const DIGITS: u8> Deserialize<'de> for UtcTimestamp<DIGITS> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Timestamp::deserialize(deserializer).map(Self::from)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl<const DIGITS: u8> JsonSchema for UtcTimestamp<DIGITS> {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"Timestamp".into()
+	}
+	fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+		Timestamp::json_schema(generator)
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Timestamp represented as integer seconds since the Unix epoch. Sub-second
+/// precision is dropped on serialization.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
+pub struct UnixSeconds {
+	ts: OffsetDateTime,
+}
+
+impl UnixSeconds {
+	/// Timestamp `s` seconds after the Unix epoch, fails outside of years
+	/// -9999 to 9999
+	pub fn from_secs(s: i64) -> Result<Self, time::error::ComponentRange> {
+		OffsetDateTime::from_unix_timestamp(s).map(Self::from)
+	}
+
+	/// Seconds since the Unix epoch, rounded down
+	#[must_use]
+	pub fn as_secs(&self) -> i64 {
+		self.ts.unix_timestamp()
+	}
+}
+
+impl fmt::Display for UnixSeconds {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "{}", self.as_secs())
+	}
+}
+
+impl str::FromStr for UnixSeconds {
+	type Err = UnixTimestampParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Self::from_secs(s.parse()?)?)
+	}
+}
+
+impl Serialize for UnixSeconds {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_i64(self.as_secs())
+	}
+}
+
+impl<'de> Deserialize<'de> for UnixSeconds {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Self::from_secs(i64::deserialize(deserializer)?).map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for UnixSeconds {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"UnixSeconds".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "integer",
+			"description": "Unix timestamp in seconds",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Timestamp represented as integer milliseconds since the Unix epoch.
+/// Sub-millisecond precision is dropped on serialization.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
+pub struct UnixMs {
+	ts: OffsetDateTime,
+}
+
+impl UnixMs {
+	/// Timestamp `ms` milliseconds after the Unix epoch, e.g. a Matrix
+	/// `origin_server_ts`. Fails outside of years -9999 to 9999.
+	pub fn from_millis(ms: i64) -> Result<Self, time::error::ComponentRange> {
+		OffsetDateTime::from_unix_timestamp_nanos(i128::from(ms) * 1_000_000).map(Self::from)
+	}
+
+	/// Milliseconds since the Unix epoch, rounded down
+	#[must_use]
+	pub fn as_millis(&self) -> i64 {
+		// `OffsetDateTime` is limited to +-9999 years which always fits into `i64`
+		// milliseconds
+		self.ts.unix_timestamp() * 1000 + i64::from(self.ts.millisecond())
+	}
+}
+
+impl fmt::Display for UnixMs {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		write!(f, "{}", self.as_millis())
+	}
+}
+
+impl str::FromStr for UnixMs {
+	type Err = UnixTimestampParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Self::from_millis(s.parse()?)?)
+	}
+}
+
+impl Serialize for UnixMs {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_i64(self.as_millis())
+	}
+}
+
+impl<'de> Deserialize<'de> for UnixMs {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Self::from_millis(i64::deserialize(deserializer)?).map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for UnixMs {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"UnixMs".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "integer",
+			"description": "Unix timestamp in milliseconds",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+/// Parsing error for [`UnixSeconds`] and [`UnixMs`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnixTimestampParseError {
+	/// Input is not an integer
+	Int(std::num::ParseIntError),
+	/// Integer is out of the supported date range
+	Range(time::error::ComponentRange),
+}
+
+impl fmt::Display for UnixTimestampParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		match self {
+			Self::Int(e) => write!(f, "invalid unix timestamp: {e}"),
+			Self::Range(e) => write!(f, "unix timestamp out of range: {e}"),
+		}
+	}
+}
+
+impl std::error::Error for UnixTimestampParseError {}
+
+impl From<std::num::ParseIntError> for UnixTimestampParseError {
+	fn from(e: std::num::ParseIntError) -> Self {
+		Self::Int(e)
+	}
+}
+
+impl From<time::error::ComponentRange> for UnixTimestampParseError {
+	fn from(e: time::error::ComponentRange) -> Self {
+		Self::Range(e)
+	}
+}
+
+/// IMF-fixdate, the preferred HTTP date format (RFC 9110, section 5.6.7)
+const IMF_FIXDATE: &[BorrowedFormatItem<'_>] = format_description!(
+	"[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+/// Timestamp represented as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+/// Parses any RFC 2822 date and always formats as IMF-fixdate in GMT.
+/// Sub-second precision is dropped on formatting.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
+pub struct HttpDate {
+	ts: OffsetDateTime,
+}
+
+impl fmt::Display for HttpDate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let ts = self.ts.to_offset(UtcOffset::UTC).format(&IMF_FIXDATE).map_err(|_| fmt::Error)?;
+		write!(f, "{}", ts)
+	}
+}
+
+impl str::FromStr for HttpDate {
+	type Err = time::error::Parse;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let ts = OffsetDateTime::parse(s, &Rfc2822)?;
+		Ok(Self { ts })
+	}
+}
+
+impl Serialize for HttpDate {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for HttpDate {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for HttpDate {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"HttpDate".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "RFC 2822 date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+const NAIVE_PARSE: &[BorrowedFormatItem<'_>] =
+	format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]");
+const NAIVE_FORMAT: &[BorrowedFormatItem<'_>] =
+	format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+const NAIVE_FORMAT_SUBSEC: &[BorrowedFormatItem<'_>] =
+	format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]");
+
+/// Timestamp represented as an ISO 8601 date and time without offset, e.g.
+/// `2024-01-01T12:00:00.5`. Parsed values are interpreted as UTC, values are
+/// converted to UTC before formatting.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
+pub struct NaiveTimestamp {
+	ts: OffsetDateTime,
+}
+
+impl fmt::Display for NaiveTimestamp {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let ts = self.ts.to_offset(UtcOffset::UTC);
+		let format = if ts.nanosecond() == 0 { NAIVE_FORMAT } else { NAIVE_FORMAT_SUBSEC };
+		let ts = ts.format(&format).map_err(|_| fmt::Error)?;
+		write!(f, "{}", ts)
+	}
+}
+
+impl str::FromStr for NaiveTimestamp {
+	type Err = time::error::Parse;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let ts = PrimitiveDateTime::parse(s, &NAIVE_PARSE)?.assume_utc();
+		Ok(Self { ts })
+	}
+}
+
+impl Serialize for NaiveTimestamp {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for NaiveTimestamp {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for NaiveTimestamp {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"NaiveTimestamp".into()
+	}
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "ISO 8601 date and time without offset, in UTC",
+		})
+	}
+	fn inline_schema() -> bool {
+		true
+	}
+}
+
+#[test]
+fn test_arithmetic() {
+	let ts: Timestamp = "2024-01-01T12:00:00Z".parse().unwrap();
+	assert_eq!((ts + Duration::hours(1)).to_string(), "2024-01-01T13:00:00Z");
+	assert_eq!((ts - StdDuration::from_secs(60)).to_string(), "2024-01-01T11:59:00Z");
+	assert_eq!((ts + Seconds::<Duration>::from_int(-30)).to_string(), "2024-01-01T11:59:30Z");
+	assert_eq!((ts + Ms::<StdDuration>::from_uint(1500)).to_string(), "2024-01-01T12:00:01.5Z");
+	assert_eq!((ts + Minutes::<Duration>::from_int(3)) - ts, Duration::minutes(3));
+
+	let mut ts2 = ts;
+	ts2 += Hours::<StdDuration>::from_uint(2);
+	ts2 -= Duration::minutes(30);
+	assert_eq!(ts2 - ts, Duration::minutes(90));
+}
+
+#[test]
+fn test_hash_consistent_with_eq() {
+	use std::collections::HashSet;
+	let a: Timestamp = "2024-01-01T12:00:00Z".parse().unwrap();
+	let b: Timestamp = "2024-01-01T14:00:00+02:00".parse().unwrap();
+	assert_eq!(a, b);
+	assert_eq!(HashSet::from([a, b]).len(), 1);
+}
+
+#[test]
+fn test_mock_clock() {
+	fn deadline(clock: &impl Clock) -> Timestamp {
+		clock.now() + Duration::minutes(10)
+	}
+
+	let start: Timestamp = "2024-01-01T12:00:00Z".parse().unwrap();
+	let clock = MockClock::new(start);
+	assert_eq!(deadline(&clock).to_string(), "2024-01-01T12:10:00Z");
+	clock.advance(Duration::minutes(5));
+	assert_eq!(clock.now() - start, Duration::minutes(5));
+
+	assert!(Timestamp::now() > start + Duration::days(365));
+	{
+		let _guard = clock.install();
+		assert_eq!(Timestamp::now(), start + Duration::minutes(5));
+		assert_eq!(SystemClock.now(), start + Duration::minutes(5));
+		{
+			let other = MockClock::new(start);
+			let _guard = other.install();
+			assert_eq!(Timestamp::now(), start);
+		}
+		clock.set(start);
+		assert_eq!(Timestamp::now(), start);
+		// Other threads keep the real time
+		std::thread::spawn(move || assert!(Timestamp::now() > start + Duration::days(365)))
+			.join()
+			.unwrap();
+	}
+	assert!(Timestamp::now() > start + Duration::days(365));
+}
+
+#[test]
+fn test_utc_timestamp() {
+	let ts: Timestamp = "2024-01-01T14:00:00.123456789+02:00".parse().unwrap();
+	assert_eq!(TimestampSecs::from(ts).to_string(), "2024-01-01T12:00:00Z");
+	assert_eq!(TimestampMillis::from(ts).to_string(), "2024-01-01T12:00:00.123Z");
+	assert_eq!(TimestampMicros::from(ts).to_string(), "2024-01-01T12:00:00.123456Z");
+	assert_eq!(UtcTimestamp::<9>::from(ts).to_string(), "2024-01-01T12:00:00.123456789Z");
+	assert_eq!(
+		serde_json::to_value(TimestampMillis::from(ts)).unwrap(),
+		serde_json::json!("2024-01-01T12:00:00.123Z")
+	);
+
+	// Same instant in different offsets
+	let a: TimestampSecs =
+		serde_json::from_value(serde_json::json!("2024-01-01T12:00:00.9Z")).unwrap();
+	let b: TimestampSecs = "2024-01-01T13:00:00+01:00".parse().unwrap();
+	assert_eq!(a, b);
+	assert_eq!(a.to_string(), b.to_string());
+	assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+
+	// Truncation rounds towards the past before the epoch as well
+	let pre_epoch: TimestampSecs = "1969-12-31T23:59:59.5Z".parse().unwrap();
+	assert_eq!(pre_epoch.to_string(), "1969-12-31T23:59:59Z");
+}
+
+#[test]
+fn test_parse_lenient() {
+	use time::macros::offset;
+
+	let utc = UtcOffset::UTC;
+	for (input, expected) in [
+		("2024-01-01T12:00:00Z", "2024-01-01T12:00:00Z"),
+		("2024-01-01t12:00:00z", "2024-01-01T12:00:00Z"),
+		("2024-01-01 12:00:00Z", "2024-01-01T12:00:00Z"),
+		("2024-01-01 12:00:00.5+02:00", "2024-01-01T12:00:00.5+02:00"),
+		("2024-01-01 12:00:00", "2024-01-01T12:00:00Z"),
+		("2024-01-01t12:00:00.25", "2024-01-01T12:00:00.25Z"),
+		(" 2024-01-01T12:00:00Z\n", "2024-01-01T12:00:00Z"),
+	] {
+		assert_eq!(Timestamp::parse_lenient(input, utc).unwrap().to_string(), expected, "{input}");
+	}
+	assert_eq!(
+		Timestamp::parse_lenient("2024-01-01 12:00:00", offset!(-5)).unwrap().to_string(),
+		"2024-01-01T12:00:00-05:00"
+	);
+	// Explicit offset wins over the default one
+	assert_eq!(
+		Timestamp::parse_lenient("2024-01-01 12:00:00Z", offset!(-5)).unwrap().to_string(),
+		"2024-01-01T12:00:00Z"
+	);
+	for input in ["2024-01-01", "12:00:00", "2024-01-01 12:00", "01.01.2024 12:00:00", ""] {
+		assert!(Timestamp::parse_lenient(input, utc).is_err(), "{input}");
+	}
+
+	// Strict parsing is unchanged
+	assert!("2024-01-01T12:00:00".parse::<Timestamp>().is_err());
+}
+
+#[test]
+fn test_lenient_timestamp_serde() {
+	let ts: LenientTimestamp =
+		serde_json::from_value(serde_json::json!("2024-01-01 12:00:00")).unwrap();
+	assert_eq!(serde_json::to_value(ts).unwrap(), serde_json::json!("2024-01-01T12:00:00Z"));
+	assert_eq!(Timestamp::from(ts), "2024-01-01T12:00:00Z".parse::<Timestamp>().unwrap());
+}
+
+#[test]
+fn test_unix_serde() {
+	let ts: Timestamp = "2024-01-01T12:00:00.123456+02:00".parse().unwrap();
+
+	let secs = UnixSeconds::from(ts);
+	assert_eq!(secs.to_string(), "1704103200");
+	assert_eq!(serde_json::to_value(secs).unwrap(), serde_json::json!(1_704_103_200));
+	let parsed: UnixSeconds = serde_json::from_value(serde_json::json!(1_704_103_200)).unwrap();
+	assert_eq!(parsed, "1704103200".parse().unwrap());
+
+	// Matrix `origin_server_ts`
+	let ms = UnixMs::from(ts);
+	assert_eq!(ms.to_string(), "1704103200123");
+	assert_eq!(serde_json::to_value(ms).unwrap(), serde_json::json!(1_704_103_200_123_i64));
+	let parsed: UnixMs = serde_json::from_value(serde_json::json!(1_704_103_200_123_i64)).unwrap();
+	assert_eq!(parsed.millisecond(), 123);
+
+	// Rounds towards the past for pre-epoch timestamps
+	assert_eq!(
+		UnixMs::from(OffsetDateTime::UNIX_EPOCH - time::Duration::microseconds(1)).to_string(),
+		"-1"
+	);
+
+	assert_eq!(UnixMs::from_millis(1_704_103_200_123).unwrap(), parsed);
+	assert_eq!(ms.as_millis(), 1_704_103_200_123);
+	assert_eq!(UnixSeconds::from_secs(1_704_103_200).unwrap().as_secs(), 1_704_103_200);
+	assert_eq!(UnixSeconds::from_secs(-1).unwrap().as_secs(), -1);
+	assert_eq!(UnixMs::from_millis(-1500).unwrap().as_millis(), -1500);
+	assert!(UnixMs::from_millis(i64::MAX).is_err());
+	assert!(UnixSeconds::from_secs(i64::MIN).is_err());
+
+	assert!(matches!("abc".parse::<UnixMs>(), Err(UnixTimestampParseError::Int(_))));
+	assert!(matches!(
+		i64::MAX.to_string().parse::<UnixSeconds>(),
+		Err(UnixTimestampParseError::Range(_))
+	));
+}
+
+#[test]
+fn test_http_date() {
+	let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+	assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+	assert_eq!(date.unix_timestamp(), 784_111_777);
+
+	let date: HttpDate =
+		serde_json::from_value(serde_json::json!("Sun, 06 Nov 1994 10:49:37 +0200")).unwrap();
+	assert_eq!(
+		serde_json::to_value(date).unwrap(),
+		serde_json::json!("Sun, 06 Nov 1994 08:49:37 GMT")
+	);
+
+	assert!("1994-11-06T08:49:37Z".parse::<HttpDate>().is_err());
+}
+
+#[test]
+fn test_naive_timestamp() {
+	let ts: NaiveTimestamp =
+		serde_json::from_value(serde_json::json!("2024-01-01T12:00:00")).unwrap();
+	assert_eq!(ts.offset(), UtcOffset::UTC);
+	assert_eq!(Timestamp::from(ts).to_string(), "2024-01-01T12:00:00Z");
+	assert_eq!(serde_json::to_value(ts).unwrap(), serde_json::json!("2024-01-01T12:00:00"));
+
+	let ts: NaiveTimestamp = "2024-01-01T12:00:00.25".parse().unwrap();
+	assert_eq!(ts.to_string(), "2024-01-01T12:00:00.25");
+
+	let ts = NaiveTimestamp::from("2024-01-01T12:00:00+02:00".parse::<Timestamp>().unwrap());
+	assert_eq!(ts.to_string(), "2024-01-01T10:00:00");
+
+	assert!("2024-01-01T12:00:00Z".parse::<NaiveTimestamp>().is_err());
+}
+
+#[test]
+#[cfg(feature = "schemars")]
+fn test_schemars() {
+	#[derive(schemars::JsonSchema)]
+	struct TestStruct {
+		_a: Timestamp,
+		_b: UnixSeconds,
+		_c: UnixMs,
+		_d: HttpDate,
+		_e: NaiveTimestamp,
+	}
+	let schema = schemars::schema_for!(TestStruct);
+	let props = &schema.as_value()["properties"];
+	assert_eq!(props["_a"]["format"], "date-time");
+	assert_eq!(props["_b"]["type"], "integer");
+	assert_eq!(props["_c"]["type"], "integer");
+	assert_eq!(props["_d"]["type"], "string");
+	assert_eq!(props["_e"]["type"], "string");
+}